@@ -0,0 +1,840 @@
+//! Lay out generated SQL for reading, e.g. in migration files and query logs.
+//!
+//! The formatter works on rendered SQL rather than on the query types, so it applies the same
+//! way to every statement, including raw SQL fragments embedded in them.
+use crate::Dialect;
+use std::ops::Range;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum KeywordCase {
+    /// Keep keywords as rendered.
+    #[default]
+    Preserve,
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Put each clause on its own line, and break long lists one item per line.
+    /// If false, the statement is rendered on a single line.
+    pub pretty: bool,
+    /// Number of spaces per indentation level.
+    pub indent: usize,
+    pub keyword_case: KeywordCase,
+    /// Clauses longer than this are broken into one item per line.
+    pub line_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            pretty: true,
+            indent: 4,
+            keyword_case: KeywordCase::Preserve,
+            line_width: 80,
+        }
+    }
+}
+
+impl FormatOptions {
+    pub fn compact() -> Self {
+        Self {
+            pretty: false,
+            ..Self::default()
+        }
+    }
+}
+
+/// Format rendered SQL. Multiple statements separated by `;` are formatted one by one.
+///
+/// Input with unbalanced parentheses is returned unchanged.
+pub fn format_sql(sql: &str, dialect: Dialect, options: &FormatOptions) -> String {
+    let mut tokens = tokenize(sql, dialect);
+    if options.keyword_case != KeywordCase::Preserve {
+        let keywords: Vec<bool> = (0..tokens.len()).map(|i| is_keyword(&tokens, i)).collect();
+        for (token, keyword) in tokens.iter_mut().zip(keywords) {
+            if !keyword {
+                continue;
+            }
+            match options.keyword_case {
+                KeywordCase::Preserve => {}
+                KeywordCase::Upper => token.text.make_ascii_uppercase(),
+                KeywordCase::Lower => token.text.make_ascii_lowercase(),
+            }
+        }
+    }
+    let Some(nodes) = parse(tokens) else {
+        return sql.to_string();
+    };
+    let formatter = Formatter { options };
+    let mut out = String::new();
+    for (i, statement) in nodes.split(|n| n.is_punct(";")).enumerate() {
+        if statement.is_empty() {
+            continue;
+        }
+        if i > 0 {
+            out.push_str(if options.pretty { ";\n" } else { "; " });
+        }
+        formatter.statement(statement, &mut out, 0);
+    }
+    if nodes.last().is_some_and(|n| n.is_punct(";")) {
+        out.push(';');
+    }
+    out
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Word,
+    Quoted,
+    /// A string or quoted identifier missing its closing quote.
+    Unterminated,
+    Punct,
+    Comment,
+    /// A `--` comment, which must be followed by a line break.
+    LineComment,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Token {
+    pub(crate) kind: Kind,
+    pub(crate) text: String,
+    /// Whether the token was preceded by whitespace.
+    pub(crate) space: bool,
+    /// Byte range of the token in the input.
+    pub(crate) span: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Token(Token),
+    Group { nodes: Vec<Node>, space: bool },
+}
+
+impl Node {
+    fn space(&self) -> bool {
+        match self {
+            Node::Token(t) => t.space,
+            Node::Group { space, .. } => *space,
+        }
+    }
+
+    /// The uppercased keyword, if this is a word.
+    fn word(&self) -> Option<String> {
+        match self {
+            Node::Token(t) if t.kind == Kind::Word => Some(t.text.to_ascii_uppercase()),
+            _ => None,
+        }
+    }
+
+    fn is_punct(&self, p: &str) -> bool {
+        matches!(self, Node::Token(t) if t.kind == Kind::Punct && t.text == p)
+    }
+
+    fn is_line_comment(&self) -> bool {
+        matches!(self, Node::Token(t) if t.kind == Kind::LineComment)
+    }
+
+    /// Whether this is a parenthesized statement, e.g. a subquery.
+    fn is_subquery(&self) -> bool {
+        let Node::Group { nodes, .. } = self else {
+            return false;
+        };
+        match nodes.first() {
+            Some(n @ Node::Group { .. }) => n.is_subquery(),
+            Some(n) => n.word().is_some_and(|w| {
+                matches!(
+                    w.as_str(),
+                    "SELECT" | "WITH" | "VALUES" | "INSERT" | "UPDATE" | "DELETE"
+                )
+            }),
+            None => false,
+        }
+    }
+}
+
+pub(crate) fn tokenize(sql: &str, dialect: Dialect) -> Vec<Token> {
+    let chars: Vec<char> = sql.chars().collect();
+    let offsets: Vec<usize> = sql
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(sql.len()))
+        .collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut space = false;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let kind = if c.is_whitespace() {
+            space = true;
+            i += 1;
+            continue;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            Kind::LineComment
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i = (i + 2).min(chars.len());
+            Kind::Comment
+        } else if c == '\'' || c == '"' || c == '`' {
+            // Postgres E'' strings and all Mysql strings use backslash escapes.
+            let backslash = c == '\''
+                && (dialect == Dialect::Mysql
+                    || tokens
+                        .last()
+                        .is_some_and(|t: &Token| !space && t.text.eq_ignore_ascii_case("e")));
+            i += 1;
+            let mut kind = Kind::Unterminated;
+            while i < chars.len() {
+                // Skip escaped characters, including doubled quotes.
+                let doubled = chars[i] == c && chars.get(i + 1) == Some(&c);
+                if doubled || (backslash && chars[i] == '\\') {
+                    i += 2;
+                } else if chars[i] == c {
+                    i += 1;
+                    kind = Kind::Quoted;
+                    break;
+                } else {
+                    i += 1;
+                }
+            }
+            i = i.min(chars.len());
+            kind
+        } else if c == '$'
+            && let Some(end) = dollar_quote_end(&chars, i)
+        {
+            i = end;
+            Kind::Quoted
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            Kind::Word
+        } else {
+            i += 1;
+            Kind::Punct
+        };
+        let text: String = chars[start..i].iter().collect();
+        let span = offsets[start]..offsets[i];
+        // Glue prefixes like E'..' and X'..' to the string.
+        if matches!(kind, Kind::Quoted | Kind::Unterminated)
+            && !space
+            && let Some(prev) = tokens.last_mut()
+            && prev.kind == Kind::Word
+            && matches!(prev.text.as_str(), "E" | "e" | "X" | "x")
+        {
+            prev.kind = kind;
+            prev.text.push_str(&text);
+            prev.span.end = span.end;
+            continue;
+        }
+        tokens.push(Token {
+            kind,
+            text,
+            space,
+            span,
+        });
+        space = false;
+    }
+    tokens
+}
+
+/// The end of a Postgres dollar-quoted string (`$tag$...$tag$`) starting at `start`.
+fn dollar_quote_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+        i += 1;
+    }
+    if chars.get(i) != Some(&'$') || chars[start + 1..i].first().is_some_and(|c| c.is_numeric()) {
+        return None;
+    }
+    let tag = &chars[start..=i];
+    (i + 1..chars.len())
+        .find(|&j| chars[j..].starts_with(tag))
+        .map(|j| j + tag.len())
+}
+
+/// Nest tokens into parenthesized groups. Returns `None` if parentheses are unbalanced.
+fn parse(tokens: Vec<Token>) -> Option<Vec<Node>> {
+    let mut stack: Vec<(Vec<Node>, bool)> = vec![(vec![], false)];
+    for token in tokens {
+        match (token.kind, token.text.as_str()) {
+            (Kind::Punct, "(") => stack.push((vec![], token.space)),
+            (Kind::Punct, ")") => {
+                if stack.len() == 1 {
+                    return None;
+                }
+                let (nodes, space) = stack.pop().unwrap();
+                stack
+                    .last_mut()
+                    .unwrap()
+                    .0
+                    .push(Node::Group { nodes, space });
+            }
+            _ => stack.last_mut().unwrap().0.push(Node::Token(token)),
+        }
+    }
+    if stack.len() != 1 {
+        return None;
+    }
+    stack.pop().map(|(nodes, _)| nodes)
+}
+
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "ALL",
+    "ALTER",
+    "AND",
+    "ANY",
+    "AS",
+    "ASC",
+    "BETWEEN",
+    "BREADTH",
+    "BY",
+    "CASCADE",
+    "CASE",
+    "CAST",
+    "COLUMN",
+    "CONFLICT",
+    "CONSTRAINT",
+    "CREATE",
+    "CROSS",
+    "CUBE",
+    "CURRENT",
+    "CYCLE",
+    "DEFAULT",
+    "DELETE",
+    "DEPTH",
+    "DESC",
+    "DISTINCT",
+    "DO",
+    "DROP",
+    "DUPLICATE",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "EXISTS",
+    "FALSE",
+    "FILTER",
+    "FIRST",
+    "FOLLOWING",
+    "FOR",
+    "FOREIGN",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "GROUPING",
+    "GROUPS",
+    "HAVING",
+    "IF",
+    "IGNORE",
+    "ILIKE",
+    "IN",
+    "INDEX",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "KEY",
+    "LAST",
+    "LATERAL",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "LOCKED",
+    "MATERIALIZED",
+    "NATURAL",
+    "NO",
+    "NOT",
+    "NOTHING",
+    "NOWAIT",
+    "NULL",
+    "NULLS",
+    "OF",
+    "OFFSET",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OVER",
+    "PARTITION",
+    "PRECEDING",
+    "PRIMARY",
+    "RANGE",
+    "RECURSIVE",
+    "REFERENCES",
+    "REPLACE",
+    "RETURNING",
+    "RIGHT",
+    "ROLLUP",
+    "ROW",
+    "ROWS",
+    "SEARCH",
+    "SELECT",
+    "SET",
+    "SETS",
+    "SHARE",
+    "SKIP",
+    "TABLE",
+    "THEN",
+    "TRUE",
+    "UNBOUNDED",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "WHEN",
+    "WHERE",
+    "WINDOW",
+    "WITH",
+];
+
+/// Whether the word at `i` is used as a keyword. Words that are also common column names, like
+/// `first` or `key`, are only keywords in the phrases that use them, e.g. `NULLS FIRST`.
+fn is_keyword(tokens: &[Token], i: usize) -> bool {
+    let token = &tokens[i];
+    let word = token.text.to_ascii_uppercase();
+    if token.kind != Kind::Word || !KEYWORDS.contains(&word.as_str()) {
+        return false;
+    }
+    let prev = i.checked_sub(1).map(|i| &tokens[i]);
+    let next = tokens.get(i + 1);
+    // Qualified words, like `u.key`, are names.
+    let is_dot = |t: Option<&Token>| t.is_some_and(|t| t.kind == Kind::Punct && t.text == ".");
+    if (is_dot(prev) && !token.space) || (is_dot(next) && !next.unwrap().space) {
+        return false;
+    }
+    let word_of = |t: Option<&Token>| {
+        t.filter(|t| t.kind == Kind::Word)
+            .map(|t| t.text.to_ascii_uppercase())
+    };
+    let (prev, next_word) = (word_of(prev), word_of(next));
+    let after = |words: &[&str]| prev.as_deref().is_some_and(|p| words.contains(&p));
+    let before = |words: &[&str]| next_word.as_deref().is_some_and(|n| words.contains(&n));
+    match word.as_str() {
+        "FIRST" => after(&["NULLS", "DEPTH", "BREADTH"]),
+        "LAST" => after(&["NULLS"]),
+        "NULLS" => before(&["FIRST", "LAST"]),
+        "KEY" => after(&["PRIMARY", "FOREIGN", "DUPLICATE", "NO"]),
+        "DUPLICATE" | "NO" => before(&["KEY"]),
+        "CURRENT" => before(&["ROW"]),
+        "ROW" => after(&["CURRENT"]) || next.is_some_and(|t| t.text == "("),
+        "ROWS" | "RANGE" | "GROUPS" => before(&["BETWEEN", "UNBOUNDED", "CURRENT"]),
+        "SHARE" => after(&["FOR", "KEY"]),
+        "INDEX" => after(&["CREATE", "UNIQUE", "DROP"]),
+        "SEARCH" => before(&["DEPTH", "BREADTH"]),
+        "DEPTH" | "BREADTH" => after(&["SEARCH"]),
+        "PARTITION" => before(&["BY"]),
+        "SKIP" => before(&["LOCKED"]),
+        "LOCKED" => after(&["SKIP"]),
+        "IGNORE" => after(&["INSERT", "OR"]),
+        "NOTHING" => after(&["DO"]),
+        "CONFLICT" => after(&["ON"]),
+        "SETS" => after(&["GROUPING"]),
+        "MATERIALIZED" => after(&["AS", "NOT"]),
+        "FILTER" => next.is_some_and(|t| t.text == "("),
+        _ => true,
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum StatementKind {
+    Query,
+    Insert,
+    Update,
+    Delete,
+}
+
+struct Formatter<'a> {
+    options: &'a FormatOptions,
+}
+
+impl Formatter<'_> {
+    /// Start a new line, unless a line comment already ended the previous one.
+    fn indent(&self, out: &mut String, level: usize) {
+        out.truncate(out.trim_end_matches(' ').len());
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&" ".repeat(level * self.options.indent));
+    }
+
+    /// Write nodes on one line, keeping their original spacing. If `expand` is set, subqueries
+    /// are laid out as statements, indented one level deeper.
+    fn inline(&self, nodes: &[Node], out: &mut String, level: usize, expand: bool) {
+        for (i, node) in nodes.iter().enumerate() {
+            if i > 0 && nodes[i - 1].is_line_comment() {
+                self.indent(out, level);
+            } else if i > 0 && node.space() {
+                out.push(' ');
+            }
+            match node {
+                Node::Token(t) => out.push_str(&t.text),
+                Node::Group { nodes, .. } if expand && node.is_subquery() => {
+                    out.push('(');
+                    self.indent(out, level + 1);
+                    self.statement(nodes, out, level + 1);
+                    self.indent(out, level);
+                    out.push(')');
+                }
+                Node::Group { nodes, .. } => {
+                    out.push('(');
+                    self.inline(nodes, out, level, expand);
+                    out.push(')');
+                }
+            }
+        }
+        if nodes.last().is_some_and(|n| n.is_line_comment()) {
+            self.indent(out, level);
+        }
+    }
+
+    fn flat_len(&self, nodes: &[Node]) -> usize {
+        let mut s = String::new();
+        self.inline(nodes, &mut s, 0, false);
+        s.chars().count()
+    }
+
+    /// Write a list of items, one per line, followed by commas.
+    fn list(&self, items: &[&[Node]], out: &mut String, level: usize) {
+        for (i, item) in items.iter().enumerate() {
+            self.indent(out, level);
+            self.inline(item, out, level, true);
+            if i + 1 < items.len() {
+                out.push(',');
+            }
+        }
+    }
+
+    fn statement(&self, nodes: &[Node], out: &mut String, level: usize) {
+        if !self.options.pretty {
+            return self.inline(nodes, out, level, false);
+        }
+        let first = nodes.first().and_then(Node::word);
+        let second = nodes.get(1).and_then(Node::word);
+        match (first.as_deref(), second.as_deref()) {
+            (Some("CREATE"), Some("TABLE")) => self.create_table(nodes, out, level),
+            (Some("ALTER"), Some("TABLE")) => self.alter_table(nodes, out, level),
+            (Some("CREATE" | "ALTER" | "DROP"), _) => self.inline(nodes, out, level, true),
+            _ => self.clauses(nodes, out, level),
+        }
+    }
+
+    fn create_table(&self, nodes: &[Node], out: &mut String, level: usize) {
+        let Some(g) = nodes.iter().position(|n| matches!(n, Node::Group { .. })) else {
+            return self.inline(nodes, out, level, true);
+        };
+        let Node::Group {
+            nodes: columns,
+            space,
+        } = &nodes[g]
+        else {
+            unreachable!()
+        };
+        self.inline(&nodes[..g], out, level, true);
+        out.push_str(if *space { " (" } else { "(" });
+        let items: Vec<&[Node]> = columns.split(|n| n.is_punct(",")).collect();
+        self.list(&items, out, level + 1);
+        self.indent(out, level);
+        out.push(')');
+        if g + 1 < nodes.len() {
+            out.push(' ');
+            self.inline(&nodes[g + 1..], out, level, true);
+        }
+    }
+
+    fn alter_table(&self, nodes: &[Node], out: &mut String, level: usize) {
+        // ALTER TABLE [IF EXISTS] [ONLY] name[.name]
+        let mut i = 2;
+        while nodes
+            .get(i)
+            .and_then(Node::word)
+            .is_some_and(|w| matches!(w.as_str(), "IF" | "EXISTS" | "ONLY"))
+        {
+            i += 1;
+        }
+        i += 1;
+        while nodes.get(i).is_some_and(|n| n.is_punct(".")) {
+            i += 2;
+        }
+        let i = i.min(nodes.len());
+        let actions: Vec<&[Node]> = nodes[i..].split(|n| n.is_punct(",")).collect();
+        let fits = level * self.options.indent + self.flat_len(nodes) <= self.options.line_width;
+        if actions.len() < 2 && fits {
+            return self.inline(nodes, out, level, true);
+        }
+        self.inline(&nodes[..i], out, level, true);
+        self.list(&actions, out, level + 1);
+    }
+
+    fn clauses(&self, nodes: &[Node], out: &mut String, level: usize) {
+        let kind = nodes
+            .iter()
+            .filter_map(Node::word)
+            .find_map(|w| match w.as_str() {
+                "SELECT" | "VALUES" => Some(StatementKind::Query),
+                "INSERT" | "REPLACE" => Some(StatementKind::Insert),
+                "UPDATE" => Some(StatementKind::Update),
+                "DELETE" => Some(StatementKind::Delete),
+                _ => None,
+            })
+            .unwrap_or(StatementKind::Query);
+        let mut starts = vec![];
+        let mut in_join = false;
+        let mut i = 0;
+        while i < nodes.len() {
+            match clause_keyword(nodes, i, kind, in_join) {
+                Some(len) => {
+                    in_join = nodes[i..i + len]
+                        .iter()
+                        .any(|n| n.word().as_deref() == Some("JOIN"));
+                    starts.push((i, len));
+                    i += len;
+                }
+                None => i += 1,
+            }
+        }
+        if starts.first().is_none_or(|&(start, _)| start > 0) {
+            starts.insert(0, (0, 0));
+        }
+        for (c, &(start, len)) in starts.iter().enumerate() {
+            let end = starts.get(c + 1).map(|&(s, _)| s).unwrap_or(nodes.len());
+            if c > 0 {
+                self.indent(out, level);
+            }
+            let keyword = &nodes[start..start + len];
+            let body = &nodes[start + len..end];
+            self.inline(keyword, out, level, false);
+            if body.is_empty() {
+                continue;
+            }
+            let items: Vec<&[Node]> = body.split(|n| n.is_punct(",")).collect();
+            let width =
+                level * self.options.indent + self.flat_len(keyword) + 1 + self.flat_len(body);
+            let fits = width <= self.options.line_width
+                && !body.iter().any(|n| n.is_subquery() || n.is_line_comment());
+            if fits || items.len() < 2 {
+                if !keyword.is_empty() {
+                    out.push(' ');
+                }
+                self.inline(body, out, level, true);
+            } else {
+                self.list(&items, out, level + 1);
+            }
+        }
+    }
+}
+
+/// If a clause starts at `i`, the number of nodes in its keyword.
+fn clause_keyword(nodes: &[Node], i: usize, kind: StatementKind, in_join: bool) -> Option<usize> {
+    use StatementKind::*;
+    let words: Vec<String> = nodes[i..].iter().take(5).map_while(Node::word).collect();
+    let w: Vec<&str> = words.iter().map(String::as_str).collect();
+    let prev = i.checked_sub(1).and_then(|p| nodes[p].word());
+    // A keyword directly followed by parentheses is a function call, e.g. VALUES(col).
+    let is_call = nodes
+        .get(i + 1)
+        .is_some_and(|n| matches!(n, Node::Group { space: false, .. }));
+    let len = match w.as_slice() {
+        [] => return None,
+        ["WITH", "RECURSIVE", ..] if i == 0 => 2,
+        ["WITH", ..] if i == 0 => 1,
+        ["SELECT", ..] => 1,
+        ["VALUES", ..] if !is_call && prev.as_deref() != Some("DEFAULT") => 1,
+        ["FROM", ..] if prev.as_deref() != Some("DISTINCT") => 1,
+        ["WHERE", ..] | ["HAVING", ..] | ["WINDOW", ..] | ["LIMIT", ..] | ["OFFSET", ..] => 1,
+        ["RETURNING", ..] => 1,
+        ["GROUP", "BY", ..] | ["ORDER", "BY", ..] => 2,
+        ["FOR", "UPDATE", ..] | ["FOR", "SHARE", ..] => 2,
+        ["FOR", "NO", "KEY", "UPDATE", ..] => 4,
+        ["FOR", "KEY", "SHARE", ..] => 3,
+        ["UNION" | "INTERSECT" | "EXCEPT", "ALL" | "DISTINCT", ..] => 2,
+        ["UNION" | "INTERSECT" | "EXCEPT", ..] => 1,
+        ["INSERT", "INTO", ..] | ["REPLACE", "INTO", ..] if i == 0 || kind == Insert => 2,
+        ["INSERT", "IGNORE", "INTO", ..] => 3,
+        ["INSERT", "OR", _, "INTO", ..] => 4,
+        ["ON", "CONFLICT", ..] if kind == Insert => 2,
+        ["DO", "UPDATE", "SET", ..] if kind == Insert => 3,
+        ["ON", "DUPLICATE", "KEY", "UPDATE", ..] if kind == Insert => 4,
+        ["UPDATE", ..] if kind == Update && prev.is_none_or(|p| p != "FOR") => 1,
+        ["SET", ..] if kind == Update => 1,
+        ["DELETE", "FROM", ..] if kind == Delete => 2,
+        ["DELETE", ..] if kind == Delete => 1,
+        ["USING", ..] if kind == Delete && !in_join => 1,
+        _ => return join_keyword(&w),
+    };
+    Some(len)
+}
+
+/// `[NATURAL] [LEFT | RIGHT | FULL | CROSS | INNER] [OUTER] JOIN`
+fn join_keyword(words: &[&str]) -> Option<usize> {
+    let mut i = 0;
+    if words.get(i) == Some(&"NATURAL") {
+        i += 1;
+    }
+    if words
+        .get(i)
+        .is_some_and(|w| matches!(*w, "LEFT" | "RIGHT" | "FULL" | "CROSS" | "INNER"))
+    {
+        i += 1;
+    }
+    if words.get(i) == Some(&"OUTER") {
+        i += 1;
+    }
+    (words.get(i) == Some(&"JOIN")).then_some(i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{Expr, From, Function, Join, OrderBy, Update};
+    use crate::schema::{Column, Table};
+    use crate::{Param, Select, ToSql, Type};
+
+    #[test]
+    fn test_select() {
+        let recent = Select::default()
+            .select_raw("user_id")
+            .from("orders")
+            .where_raw("created_at > now() - interval '1 day'");
+        let select = Select::default()
+            .select_raw("u.id")
+            .select_raw("u.name")
+            .select_expr(Function::count_star(), "order_count")
+            .select_expr(
+                Function::max(Expr::table_column("o", "created_at")),
+                "last_order",
+            )
+            .from(From::table("users").alias("u"))
+            .join(Join::left("orders").alias("o").on_raw("o.user_id = u.id"))
+            .where_(Expr::in_select(Expr::table_column("u", "id"), recent).into())
+            .group_by("u.id")
+            .order_by(OrderBy::new("order_count").desc())
+            .limit(10);
+        let expected = r#"
+SELECT
+    u.id,
+    u.name,
+    count(*) AS "order_count",
+    max("o"."created_at") AS "last_order"
+FROM "users" AS "u"
+LEFT JOIN "orders" AS "o" ON o.user_id = u.id
+WHERE "u"."id" IN (
+    SELECT user_id
+    FROM "orders"
+    WHERE created_at > now() - interval '1 day'
+)
+GROUP BY u.id
+ORDER BY order_count DESC
+LIMIT 10"#;
+        let options = FormatOptions::default();
+        assert_eq!(
+            select.to_sql_formatted(Dialect::Postgres, &options),
+            expected.trim()
+        );
+
+        let options = FormatOptions {
+            keyword_case: KeywordCase::Lower,
+            ..FormatOptions::compact()
+        };
+        assert_eq!(
+            Select::default()
+                .select_raw("1")
+                .from("t")
+                .limit(1)
+                .to_sql_formatted(Dialect::Postgres, &options),
+            r#"select 1 from "t" limit 1"#
+        );
+
+        let (sql, params) = Select::default()
+            .select_raw("*")
+            .from("users")
+            .where_(Expr::new_eq(Expr::column("id"), Expr::param(7)).into())
+            .to_sql_formatted_with_params(Dialect::Postgres, &FormatOptions::default());
+        assert_eq!(sql, "SELECT *\nFROM \"users\"\nWHERE \"id\" = $1");
+        assert_eq!(params, vec![Param::Int(7)]);
+
+        let options = FormatOptions {
+            keyword_case: KeywordCase::Upper,
+            ..FormatOptions::compact()
+        };
+        let sql = "select first, u.key from users u order by last nulls first for no key update";
+        assert_eq!(
+            format_sql(sql, Dialect::Postgres, &options),
+            "SELECT first, u.key FROM users u ORDER BY last NULLS FIRST FOR NO KEY UPDATE"
+        );
+    }
+
+    #[test]
+    fn test_ddl() {
+        let column = |name: &str, typ: Type, primary_key: bool| Column {
+            name: name.to_string(),
+            typ,
+            nullable: false,
+            primary_key,
+            default: None,
+            constraint: None,
+            generated: None,
+        };
+        let table = Table::new("users")
+            .column(column("id", Type::I64, true))
+            .column(column("name", Type::Text, false));
+        let expected = r#"
+CREATE TABLE IF NOT EXISTS "users" (
+  "id" bigint NOT NULL PRIMARY KEY,
+  "name" character varying NOT NULL
+)"#;
+        let options = FormatOptions {
+            indent: 2,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            table.to_sql_formatted(Dialect::Postgres, &options),
+            expected.trim()
+        );
+        assert_eq!(
+            table.to_sql_formatted(Dialect::Postgres, &FormatOptions::compact()),
+            r#"CREATE TABLE IF NOT EXISTS "users" ("id" bigint NOT NULL PRIMARY KEY, "name" character varying NOT NULL)"#
+        );
+
+        let sql =
+            "ALTER TABLE \"users\" ADD COLUMN \"email\" text, ALTER COLUMN \"name\" SET NOT NULL";
+        assert_eq!(
+            format_sql(sql, Dialect::Postgres, &FormatOptions::default()),
+            "ALTER TABLE \"users\"\n    ADD COLUMN \"email\" text,\n    ALTER COLUMN \"name\" SET NOT NULL"
+        );
+    }
+
+    #[test]
+    fn test_update() {
+        let update = Update::new("users")
+            .set(
+                "name",
+                Expr::Raw("'it''s (not) a clause; FROM'".to_string()),
+            )
+            .set("email", Expr::Raw("/* TODO */".to_string()))
+            .where_raw("id = 1")
+            .returning(&["id"]);
+        let expected = r#"
+UPDATE "users"
+SET "name" = 'it''s (not) a clause; FROM', "email" = /* TODO */
+WHERE id = 1
+RETURNING "id""#;
+        assert_eq!(
+            update.to_sql_formatted(Dialect::Postgres, &FormatOptions::default()),
+            expected.trim()
+        );
+    }
+}
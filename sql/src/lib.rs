@@ -5,8 +5,14 @@ pub mod query;
 /// Defines structs and functions for representing SQL database schemas.
 pub mod schema;
 
+mod format;
+mod parse;
 mod to_sql;
 pub mod util;
+/// Traverse query and schema trees.
+pub mod visit;
+/// Traverse and rewrite query and schema trees.
+pub mod visit_mut;
 
 #[doc(inline)]
 pub use migrate::{migrate, Migration, MigrationOptions};
@@ -15,4 +21,8 @@ pub use query::*;
 #[doc(inline)]
 pub use schema::*;
 #[doc(inline)]
-pub use to_sql::{Dialect, ToSql};
+pub use parse::FromDdl;
+#[doc(inline)]
+pub use format::{FormatOptions, KeywordCase, format_sql};
+#[doc(inline)]
+pub use to_sql::{Dialect, Param, ToSql};
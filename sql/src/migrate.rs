@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::query::{AlterTable, Update};
 use crate::{AlterAction, Constraint, Dialect, DropTable, Index, Schema, Table, ToSql};
@@ -61,15 +61,15 @@ pub fn migrate(current: Schema, desired: Schema, options: &MigrationOptions) ->
                         desired_column.typ.clone(),
                     ));
                 };
-                if desired_column.constraint.is_some() && current.constraint.is_none() {
-                    if let Some(c) = &desired_column.constraint {
-                        let name = desired_column.name.clone();
-                        actions.push(AlterAction::add_constraint(
-                            &desired_table.name,
-                            name,
-                            c.clone(),
-                        ));
-                    }
+                if current.constraint.is_none()
+                    && let Some(c) = &desired_column.constraint
+                {
+                    let name = desired_column.name.clone();
+                    actions.push(AlterAction::add_constraint(
+                        &desired_table.name,
+                        name,
+                        c.clone(),
+                    ));
                 }
             } else {
                 // add the column can be in 1 step if the column is nullable
@@ -129,6 +129,20 @@ pub fn migrate(current: Schema, desired: Schema, options: &MigrationOptions) ->
         }
     }
 
+    // new indexes, matched by name
+    let current_indexes = current
+        .indexes
+        .iter()
+        .map(|i| &i.name)
+        .collect::<HashSet<_>>();
+    for index in desired
+        .indexes
+        .iter()
+        .filter(|i| !current_indexes.contains(&i.name))
+    {
+        statements.push(Statement::CreateIndex(index.clone()));
+    }
+
     // Sort statements topologically based on foreign key dependencies
     let sorted_statements = topologically_sort_statements(&statements, &desired_tables);
 
@@ -214,6 +228,7 @@ fn topologically_sort_statements(
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Migration {
     pub statements: Vec<Statement>,
     pub debug_results: Vec<DebugResults>,
@@ -232,6 +247,7 @@ impl Migration {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement {
     CreateTable(Table),
     CreateIndex(Index),
@@ -286,6 +302,7 @@ impl ToSql for Statement {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DebugResults {
     TablesIdentical(String),
     SkippedDropTable(String),
@@ -314,8 +331,10 @@ mod tests {
         let mut single_table_schema = Schema::default();
         let t = Table::new("new_table");
         single_table_schema.tables.push(t.clone());
-        let mut allow_destructive_options = MigrationOptions::default();
-        allow_destructive_options.allow_destructive = true;
+        let allow_destructive_options = MigrationOptions {
+            allow_destructive: true,
+            ..Default::default()
+        };
 
         let mut migrations = migrate(
             single_table_schema,
@@ -333,6 +352,30 @@ mod tests {
         assert_eq!(statement, expected_statement);
     }
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let mut current = Schema::default();
+        current.tables.push(Table::new("team"));
+        let mut desired = Schema::default();
+        desired.tables.push(Table::new("team").column(Column {
+            name: "name".to_string(),
+            typ: Type::Text,
+            nullable: true,
+            primary_key: false,
+            default: None,
+            constraint: None,
+            generated: None,
+        }));
+        desired.tables.push(Table::new("user"));
+        let migration = migrate(current, desired, &MigrationOptions::default()).unwrap();
+        assert_eq!(migration.statements.len(), 2);
+
+        let json = serde_json::to_string(&migration).unwrap();
+        let deserialized: Migration = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.statements, migration.statements);
+    }
+
     #[test]
     fn test_drop_table_without_destructive_operations() {
         let empty_schema = Schema::default();
@@ -345,6 +388,29 @@ mod tests {
         assert!(migrations.statements.is_empty());
     }
 
+    #[test]
+    fn test_create_index() {
+        let index = |name: &str, column: &str| Index {
+            name: name.to_string(),
+            unique: false,
+            schema: None,
+            table: "user".to_string(),
+            columns: vec![column.to_string()],
+            kind: Default::default(),
+        };
+        let mut current = Schema::default();
+        current.tables.push(Table::new("user"));
+        current.indexes.push(index("user_email_idx", "email"));
+        let mut desired = current.clone();
+        desired.indexes.push(index("user_name_idx", "name"));
+
+        let migration = migrate(current, desired, &MigrationOptions::default()).unwrap();
+        assert_eq!(
+            migration.statements,
+            vec![Statement::CreateIndex(index("user_name_idx", "name"))]
+        );
+    }
+
     #[test]
     fn test_topological_sort_statements() {
         let empty_schema = Schema::default();
@@ -378,6 +444,7 @@ mod tests {
                 primary_key: false,
                 default: None,
                 constraint: Some(Constraint::ForeignKey(ForeignKey {
+                    schema: None,
                     table: "team".to_string(),
                     columns: vec!["id".to_string()],
                 })),
@@ -0,0 +1,2604 @@
+//! Parse SQL text into the query and schema types.
+//!
+//! The parser covers the SQL the types can represent. An expression it does not understand,
+//! e.g. `data->>'key'` or `ARRAY[1, 2]`, is kept as `Expr::Raw` with its original text, so it is
+//! rendered unchanged for the dialect it was written in. A clause that has no representation,
+//! e.g. a `UNIQUE` column constraint, is an error.
+use crate::{Dialect, ToSql};
+use crate::format::{Kind, Token, tokenize};
+use crate::query::{
+    AlterAction, AlterColumnAction, AlterTable, Assignment, Case, Compound, CompoundQuery,
+    Conflict, Criteria, Cte, CteQuery, Delete, Distinct, Expr, From, Function, GroupBy, Insert,
+    Join, JoinTable, JoinType, Literal, Lock, OnConflict, Operation, OrderBy, Over, Select,
+    SelectColumn, SelectExpression, SetOperation, SetOperator, Update, Value, Values, Where,
+    Window,
+};
+use crate::query::{Frame, FrameBound, FrameUnit};
+use crate::schema::{
+    Column, Constraint, ForeignKey, Generated, GenerationTime, GenerationValue, Index, IndexKind,
+    Schema, Table, Type,
+};
+use anyhow::{Result, anyhow, bail};
+use std::str::FromStr;
+
+impl Select {
+    /// Parse a SELECT statement. A trailing `;` is allowed.
+    ///
+    /// Unquoted identifiers are folded to lowercase on Postgres, as the database does. ORDER BY
+    /// expressions are kept as written. Placeholders (`$1`, `?`) are kept as `Expr::Raw`.
+    pub fn parse(sql: &str, dialect: Dialect) -> Result<Self> {
+        Parser::new(sql, dialect).statement(|p| match p.query()? {
+            Query::Select(select) => Ok(select),
+            Query::Compound(_) => bail!("Expected a SELECT, found a compound query"),
+        })
+    }
+}
+
+impl Compound {
+    /// Parse a query combined with `UNION`, `INTERSECT` or `EXCEPT`.
+    pub fn parse(sql: &str, dialect: Dialect) -> Result<Self> {
+        Parser::new(sql, dialect).statement(|p| match p.query()? {
+            Query::Compound(compound) => Ok(compound),
+            Query::Select(_) => bail!("Expected a compound query, found a SELECT"),
+        })
+    }
+}
+
+impl Insert {
+    /// Parse an INSERT (or Mysql/Sqlite `REPLACE`) statement.
+    ///
+    /// `ON CONFLICT (...) DO NOTHING` is read as `OnConflict::Ignore`, which ignores conflicts on
+    /// any unique constraint, not just the given one.
+    pub fn parse(sql: &str, dialect: Dialect) -> Result<Self> {
+        Parser::new(sql, dialect).statement(Parser::insert)
+    }
+}
+
+impl Update {
+    pub fn parse(sql: &str, dialect: Dialect) -> Result<Self> {
+        Parser::new(sql, dialect).statement(Parser::update)
+    }
+}
+
+impl Delete {
+    pub fn parse(sql: &str, dialect: Dialect) -> Result<Self> {
+        Parser::new(sql, dialect).statement(Parser::delete)
+    }
+}
+
+impl Table {
+    /// Parse a CREATE TABLE statement.
+    ///
+    /// Single-column `PRIMARY KEY` and `FOREIGN KEY` table constraints are moved onto their
+    /// column. Table options after the column list, e.g. Mysql's `ENGINE`, are ignored.
+    pub fn parse(sql: &str, dialect: Dialect) -> Result<Self> {
+        Parser::new(sql, dialect).statement(Parser::create_table)
+    }
+}
+
+impl Index {
+    pub fn parse(sql: &str, dialect: Dialect) -> Result<Self> {
+        Parser::new(sql, dialect).statement(Parser::create_index)
+    }
+}
+
+impl AlterTable {
+    pub fn parse(sql: &str, dialect: Dialect) -> Result<Self> {
+        Parser::new(sql, dialect).statement(Parser::alter_table)
+    }
+}
+
+/// Build a schema from SQL DDL, e.g. the contents of a directory of `.sql` files.
+pub trait FromDdl: Sized {
+    fn try_from_ddl(sql: &str, dialect: Dialect) -> Result<Self>;
+}
+
+impl FromDdl for Schema {
+    /// Reads `CREATE TABLE`, `CREATE INDEX` and `ALTER TABLE` statements. `ALTER TABLE` can add
+    /// columns, primary keys and single-column foreign keys, and change column types and
+    /// nullability, of tables created earlier in the DDL. Multi-column foreign keys, and `UNIQUE`
+    /// and `CHECK` constraints, are not supported.
+    ///
+    /// Every statement that can't be read is reported, with its line number.
+    fn try_from_ddl(sql: &str, dialect: Dialect) -> Result<Self> {
+        let parser = Parser::new(sql, dialect);
+        let mut schema = Schema::default();
+        let mut errors = vec![];
+        for tokens in parser
+            .tokens
+            .split(|t| t.kind == Kind::Punct && t.text == ";")
+        {
+            let Some(first) = tokens.first() else {
+                continue;
+            };
+            let line = parser.line(first.span.start);
+            let mut statement = Parser {
+                sql,
+                tokens: tokens.to_vec(),
+                pos: 0,
+                dialect,
+            };
+            let result = statement
+                .check_quotes()
+                .and_then(|_| statement.ddl_statement(&mut schema))
+                .and_then(|_| match statement.peek() {
+                    Some(_) => Err(statement.unexpected("end of statement")),
+                    None => Ok(()),
+                });
+            // Most errors name the line of the token they refer to, the rest get the statement's.
+            match result.map_err(|e| e.to_string()) {
+                Err(e) if e.contains(" at line ") => errors.push(e),
+                Err(e) => errors.push(format!("{} at line {}", e, line)),
+                Ok(()) => {}
+            }
+        }
+        if !errors.is_empty() {
+            bail!("Failed to read schema from DDL:\n{}", errors.join("\n"));
+        }
+        Ok(schema)
+    }
+}
+
+/// Keywords that end an expression.
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "AS",
+    "ASC",
+    "CROSS",
+    "DESC",
+    "DO",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "FETCH",
+    "FOR",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "INNER",
+    "INTERSECT",
+    "INTO",
+    "JOIN",
+    "LEFT",
+    "LIMIT",
+    "LOCK",
+    "NATURAL",
+    "NULLS",
+    "OFFSET",
+    "ON",
+    "ORDER",
+    "OUTER",
+    "RETURNING",
+    "RIGHT",
+    "SELECT",
+    "SET",
+    "THEN",
+    "UNION",
+    "USING",
+    "VALUES",
+    "WHEN",
+    "WHERE",
+    "WINDOW",
+    "WITH",
+];
+
+/// Keywords that can't be identifiers, but may continue an expression.
+const EXPR_KEYWORDS: &[&str] = &[
+    "ALL",
+    "AND",
+    "ANY",
+    "BETWEEN",
+    "CASE",
+    "CAST",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "DEFAULT",
+    "DISTINCT",
+    "EXISTS",
+    "FALSE",
+    "ILIKE",
+    "IN",
+    "IS",
+    "LATERAL",
+    "LIKE",
+    "NOT",
+    "NULL",
+    "OR",
+    "SOME",
+    "TRUE",
+];
+
+/// Keywords that start a column constraint in CREATE TABLE.
+const COLUMN_CONSTRAINTS: &[&str] = &[
+    "CHECK",
+    "COLLATE",
+    "CONSTRAINT",
+    "DEFAULT",
+    "GENERATED",
+    "NOT",
+    "NULL",
+    "PRIMARY",
+    "REFERENCES",
+    "UNIQUE",
+];
+
+/// Characters that combine into operators, e.g. `<=` or `->>`.
+const OPERATOR_CHARS: &str = "+-*/<>=~!@#%^&|";
+
+fn is_reserved(word: &str) -> bool {
+    let upper = word.to_ascii_uppercase();
+    CLAUSE_KEYWORDS.contains(&upper.as_str()) || EXPR_KEYWORDS.contains(&upper.as_str())
+}
+
+#[allow(clippy::large_enum_variant)]
+enum Query {
+    Select(Select),
+    Compound(Compound),
+}
+
+impl Query {
+    fn into_compound_query(self) -> CompoundQuery {
+        match self {
+            Query::Select(select) => select.into(),
+            Query::Compound(compound) => compound.into(),
+        }
+    }
+}
+
+/// A row source in FROM or JOIN, before we know which one it is.
+struct Source {
+    table: JoinTable,
+    alias: Option<String>,
+    columns: Vec<String>,
+    lateral: bool,
+}
+
+impl Source {
+    fn into_from(self) -> Result<From> {
+        if self.lateral {
+            bail!("LATERAL is only supported on joined tables");
+        }
+        let derived_alias = |alias: Option<String>| {
+            alias.ok_or_else(|| anyhow!("A subquery or VALUES list in FROM must have an alias"))
+        };
+        Ok(match self.table {
+            JoinTable::Table { schema, table } if self.columns.is_empty() => From::Table {
+                schema,
+                table,
+                alias: self.alias,
+            },
+            JoinTable::Select(query) if self.columns.is_empty() => From::Select {
+                query: Box::new(query),
+                alias: derived_alias(self.alias)?,
+            },
+            JoinTable::Compound(query) if self.columns.is_empty() => From::Compound {
+                query: Box::new(query),
+                alias: derived_alias(self.alias)?,
+            },
+            JoinTable::Function(function) => From::Function {
+                function,
+                alias: self.alias,
+                columns: self.columns,
+            },
+            JoinTable::Values(rows) => From::Values {
+                rows,
+                alias: derived_alias(self.alias)?,
+                columns: self.columns,
+            },
+            _ => bail!("Column aliases are only supported on table functions and VALUES lists"),
+        })
+    }
+
+    fn into_join(self, typ: JoinType) -> Join {
+        Join {
+            typ,
+            table: self.table,
+            alias: self.alias,
+            columns: self.columns,
+            criteria: Criteria::On(Where::And(vec![])),
+            lateral: self.lateral,
+        }
+    }
+}
+
+/// Split a condition into `Where` terms, so more can be added with `Select::where_`.
+fn condition(expr: Expr) -> Where {
+    match expr {
+        Expr::And(terms) => Where::And(terms.into_iter().map(condition).collect()),
+        Expr::Or(terms) => Where::Or(terms.into_iter().map(condition).collect()),
+        expr => Where::Expr(expr),
+    }
+}
+
+/// Remove quotes, and undo doubled quote characters and backslash escapes.
+fn unescape(s: &str, quote: char, backslash: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == quote && chars.peek() == Some(&quote) {
+            chars.next();
+            out.push(quote);
+        } else if c == '\\' && backslash {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('r') => out.push('\r'),
+                Some('0') => out.push('\0'),
+                // Mysql keeps the backslash in LIKE wildcards.
+                Some(c @ ('%' | '_')) => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                Some(c) => out.push(c),
+                None => {}
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The contents of a quoted token, e.g. `'it''s'` or `"name"`, if it is terminated.
+fn quoted(text: &str, quote: char) -> Option<&str> {
+    let inner = text.strip_prefix(quote)?.strip_suffix(quote)?;
+    (!text[1..].is_empty()).then_some(inner)
+}
+
+struct Parser<'a> {
+    sql: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    dialect: Dialect,
+}
+
+impl<'a> Parser<'a> {
+    fn new(sql: &'a str, dialect: Dialect) -> Self {
+        let tokens = tokenize(sql, dialect)
+            .into_iter()
+            .filter(|t| !matches!(t.kind, Kind::Comment | Kind::LineComment))
+            .collect();
+        Self {
+            sql,
+            tokens,
+            pos: 0,
+            dialect,
+        }
+    }
+
+    /// Parse a whole statement, with an optional trailing `;`.
+    fn statement<T>(mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.check_quotes()?;
+        let result = f(&mut self)?;
+        self.eat_punct(";");
+        if self.peek().is_some() {
+            return Err(self.unexpected("end of statement"));
+        }
+        Ok(result)
+    }
+
+    /// Error on a string or quoted identifier missing its closing quote, which would otherwise
+    /// swallow the rest of the input.
+    fn check_quotes(&self) -> Result<()> {
+        match self.tokens.iter().find(|t| t.kind == Kind::Unterminated) {
+            Some(t) => bail!("Unterminated quote at line {}", self.line(t.span.start)),
+            None => Ok(()),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        self.sql[..offset].matches('\n').count() + 1
+    }
+
+    fn unexpected(&self, expected: &str) -> anyhow::Error {
+        match self.peek() {
+            Some(t) => anyhow!(
+                "Expected {} at line {}, found `{}`",
+                expected,
+                self.line(t.span.start),
+                t.text
+            ),
+            None => anyhow!("Expected {}, found end of input", expected),
+        }
+    }
+
+    fn unsupported(&self, what: &str) -> anyhow::Error {
+        match self.peek() {
+            Some(t) => anyhow!(
+                "Unsupported {} `{}` at line {}",
+                what,
+                t.text,
+                self.line(t.span.start)
+            ),
+            None => anyhow!("Unsupported {} at end of input", what),
+        }
+    }
+
+    /// The source text of the tokens from `start` up to the current position.
+    fn text(&self, start: usize) -> String {
+        let begin = self.tokens[start].span.start;
+        let end = self.tokens[self.pos - 1].span.end;
+        self.sql[begin..end].to_string()
+    }
+
+    fn is_keyword_at(&self, n: usize, keyword: &str) -> bool {
+        self.tokens
+            .get(self.pos + n)
+            .is_some_and(|t| t.kind == Kind::Word && t.text.eq_ignore_ascii_case(keyword))
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.is_keyword_at(0, keyword)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Consume a sequence of keywords, e.g. `ORDER BY`, only if all of them are present.
+    fn eat_keywords(&mut self, keywords: &[&str]) -> bool {
+        let found = self.is_keywords(keywords);
+        if found {
+            self.pos += keywords.len();
+        }
+        found
+    }
+
+    fn is_keywords(&self, keywords: &[&str]) -> bool {
+        keywords
+            .iter()
+            .enumerate()
+            .all(|(i, k)| self.is_keyword_at(i, k))
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if !self.eat_keyword(keyword) {
+            return Err(self.unexpected(keyword));
+        }
+        Ok(())
+    }
+
+    fn expect_keywords(&mut self, keywords: &[&str]) -> Result<()> {
+        if !self.eat_keywords(keywords) {
+            return Err(self.unexpected(&keywords.join(" ")));
+        }
+        Ok(())
+    }
+
+    /// The number of tokens making up `p` at the current position, if present. Operators span
+    /// several adjacent tokens, e.g. `<=`, and must not be the start of a longer operator.
+    fn punct_len(&self, p: &str) -> Option<usize> {
+        let mut n = 0;
+        for c in p.chars() {
+            let t = self.tokens.get(self.pos + n)?;
+            if t.kind != Kind::Punct || !t.text.starts_with(c) || (n > 0 && t.space) {
+                return None;
+            }
+            n += 1;
+        }
+        if p.starts_with(|c| OPERATOR_CHARS.contains(c))
+            && let Some(next) = self.tokens.get(self.pos + n)
+            && next.kind == Kind::Punct
+            && !next.space
+            && OPERATOR_CHARS.contains(next.text.as_str())
+            // e.g. `a=-1`
+            && !matches!(next.text.as_str(), "+" | "-")
+        {
+            return None;
+        }
+        Some(n)
+    }
+
+    fn is_punct(&self, p: &str) -> bool {
+        self.punct_len(p).is_some()
+    }
+
+    fn eat_punct(&mut self, p: &str) -> bool {
+        match self.punct_len(p) {
+            Some(n) => {
+                self.pos += n;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect_punct(&mut self, p: &str) -> Result<()> {
+        if !self.eat_punct(p) {
+            return Err(self.unexpected(&format!("`{}`", p)));
+        }
+        Ok(())
+    }
+
+    /// The quote character that delimits identifiers in the dialect.
+    fn is_identifier_quote(&self, c: char) -> bool {
+        match self.dialect {
+            Dialect::Postgres => c == '"',
+            Dialect::Mysql => c == '`',
+            Dialect::Sqlite => c == '"' || c == '`',
+        }
+    }
+
+    fn is_ident_at(&self, n: usize) -> bool {
+        let Some(t) = self.tokens.get(self.pos + n) else {
+            return false;
+        };
+        match t.kind {
+            Kind::Word => {
+                !is_reserved(&t.text) && !t.text.starts_with(|c: char| c.is_numeric() || c == '$')
+            }
+            Kind::Quoted => t.text.starts_with(|c| self.is_identifier_quote(c)),
+            _ => false,
+        }
+    }
+
+    fn is_ident(&self) -> bool {
+        self.is_ident_at(0)
+    }
+
+    /// An identifier. Unquoted identifiers are folded to lowercase on Postgres.
+    fn ident(&mut self) -> Result<String> {
+        if !self.is_ident() {
+            return Err(self.unexpected("an identifier"));
+        }
+        let t = &self.tokens[self.pos];
+        let name = match t.kind {
+            Kind::Word if self.dialect == Dialect::Postgres => t.text.to_lowercase(),
+            Kind::Word => t.text.clone(),
+            _ => {
+                let quote = t.text.chars().next().unwrap();
+                let Some(inner) = quoted(&t.text, quote) else {
+                    return Err(self.unexpected("a closing quote"));
+                };
+                unescape(inner, quote, false)
+            }
+        };
+        self.pos += 1;
+        Ok(name)
+    }
+
+    /// A parenthesized, comma-separated list of identifiers.
+    fn ident_list(&mut self) -> Result<Vec<String>> {
+        self.expect_punct("(")?;
+        let mut idents = vec![self.ident()?];
+        while self.eat_punct(",") {
+            idents.push(self.ident()?);
+        }
+        self.expect_punct(")")?;
+        Ok(idents)
+    }
+
+    /// `[schema.]table`
+    fn table_name(&mut self) -> Result<(Option<String>, String)> {
+        let name = self.ident()?;
+        if self.eat_punct(".") {
+            Ok((Some(name), self.ident()?))
+        } else {
+            Ok((None, name))
+        }
+    }
+
+    /// `[AS] alias`
+    fn alias(&mut self) -> Result<Option<String>> {
+        if self.eat_keyword("AS") || self.is_ident() {
+            return self.ident().map(Some);
+        }
+        Ok(None)
+    }
+
+    fn usize(&mut self) -> Result<usize> {
+        match self.peek().map(|t| t.text.parse::<usize>()) {
+            Some(Ok(n)) => {
+                self.pos += 1;
+                Ok(n)
+            }
+            _ => Err(self.unexpected("a number")),
+        }
+    }
+
+    /// Whether the next token ends an expression, e.g. a clause keyword or a comma.
+    fn at_boundary(&self) -> bool {
+        match self.peek() {
+            None => true,
+            Some(t) if t.kind == Kind::Punct => matches!(t.text.as_str(), "," | ")" | ";"),
+            Some(t) if t.kind == Kind::Word => {
+                CLAUSE_KEYWORDS.contains(&t.text.to_ascii_uppercase().as_str())
+            }
+            _ => false,
+        }
+    }
+
+    /// Skip to the end of the current expression, returning its text. The expression ends at a
+    /// clause keyword or comma outside parentheses, or a keyword in `stop`.
+    fn raw_until(&mut self, stop: &[&str]) -> Result<String> {
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(t) = self.peek() {
+            let word = (t.kind == Kind::Word).then(|| t.text.to_ascii_uppercase());
+            match (t.kind, t.text.as_str(), word.as_deref()) {
+                (Kind::Punct, "(", _) | (_, _, Some("CASE")) => depth += 1,
+                (Kind::Punct, ")", _) | (_, _, Some("END")) if depth > 0 => depth -= 1,
+                (_, _, Some(w)) if depth == 0 && stop.contains(&w) => break,
+                _ if depth == 0 && self.at_boundary() => break,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.unexpected("an expression"));
+        }
+        Ok(self.text(start))
+    }
+
+    /// Skip to the closing parenthesis of the current group, returning the text inside it.
+    fn raw_group(&mut self) -> Result<String> {
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(t) = self.peek() {
+            match (t.kind, t.text.as_str()) {
+                (Kind::Punct, "(") => depth += 1,
+                (Kind::Punct, ")") if depth == 0 => break,
+                (Kind::Punct, ")") => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        if self.pos == start || self.peek().is_none() {
+            return Err(self.unexpected("`)`"));
+        }
+        Ok(self.text(start))
+    }
+
+    /// An expression, or its text as `Expr::Raw` if it isn't supported.
+    fn expr_or_raw(&mut self) -> Result<Expr> {
+        let start = self.pos;
+        match self.expr() {
+            Ok(expr) if self.at_boundary() || self.is_ident() => Ok(expr),
+            _ => {
+                self.pos = start;
+                self.raw_until(&[]).map(Expr::Raw)
+            }
+        }
+    }
+
+    fn expr_list(&mut self) -> Result<Vec<Expr>> {
+        let mut exprs = vec![self.expr()?];
+        while self.eat_punct(",") {
+            exprs.push(self.expr()?);
+        }
+        Ok(exprs)
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut terms = vec![self.and()?];
+        while self.eat_keyword("OR") {
+            terms.push(self.and()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.pop().unwrap()
+        } else {
+            Expr::Or(terms)
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut terms = vec![self.not()?];
+        while self.eat_keyword("AND") {
+            terms.push(self.not()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.pop().unwrap()
+        } else {
+            Expr::And(terms)
+        })
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::not(self.not()?));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        use Operation::*;
+        let left = self.concat()?;
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            if self.eat_keyword("NULL") {
+                return Ok(if negated {
+                    Expr::is_not_null(left)
+                } else {
+                    Expr::is_null(left)
+                });
+            }
+            self.expect_keywords(&["DISTINCT", "FROM"])?;
+            let expr = Expr::not_distinct_from(left, self.concat()?);
+            return Ok(if negated { expr } else { Expr::not(expr) });
+        }
+        if self.dialect == Dialect::Mysql && self.eat_punct("<=>") {
+            return Ok(Expr::not_distinct_from(left, self.concat()?));
+        }
+        let negated = self.is_keyword("NOT")
+            && ["IN", "LIKE", "ILIKE", "BETWEEN"]
+                .iter()
+                .any(|k| self.is_keyword_at(1, k));
+        if negated {
+            self.pos += 1;
+        }
+        if self.eat_keyword("IN") {
+            self.expect_punct("(")?;
+            let expr = if self.is_query_start() {
+                let select = self.select()?;
+                if negated {
+                    Expr::not_in_select(left, select)
+                } else {
+                    Expr::in_select(left, select)
+                }
+            } else {
+                let list = self.expr_list()?;
+                if negated {
+                    Expr::not_in_list(left, list)
+                } else {
+                    Expr::in_list(left, list)
+                }
+            };
+            self.expect_punct(")")?;
+            return Ok(expr);
+        }
+        if self.eat_keyword("BETWEEN") {
+            let low = self.concat()?;
+            self.expect_keyword("AND")?;
+            let high = self.concat()?;
+            return Ok(if negated {
+                Expr::not_between(left, low, high)
+            } else {
+                Expr::between(left, low, high)
+            });
+        }
+        for (keyword, op, not_op) in [("LIKE", Like, NotLike), ("ILIKE", ILike, NotILike)] {
+            if self.eat_keyword(keyword) {
+                let op = if negated { not_op } else { op };
+                return Ok(Expr::binop(op, left, self.concat()?));
+            }
+        }
+        if negated {
+            return Err(self.unexpected("IN, LIKE or BETWEEN"));
+        }
+        let operators = [
+            ("<>", NotEq),
+            ("!=", NotEq),
+            ("<=", Lte),
+            (">=", Gte),
+            ("=", Eq),
+            ("<", Lt),
+            (">", Gt),
+        ];
+        for (p, op) in operators {
+            if !self.eat_punct(p) {
+                continue;
+            }
+            let any = self.is_keyword("ANY") || self.is_keyword("SOME");
+            if (any || self.is_keyword("ALL")) && self.is_query_start_at(2) {
+                self.pos += 1;
+                self.expect_punct("(")?;
+                let select = self.select()?;
+                self.expect_punct(")")?;
+                return Ok(if any {
+                    Expr::any(op, left, select)
+                } else {
+                    Expr::all(op, left, select)
+                });
+            }
+            return Ok(Expr::binop(op, left, self.concat()?));
+        }
+        Ok(left)
+    }
+
+    fn concat(&mut self) -> Result<Expr> {
+        let mut expr = self.additive()?;
+        // `||` is logical OR on Mysql, and binds tighter than `*` on Sqlite.
+        while self.dialect == Dialect::Postgres && self.eat_punct("||") {
+            expr = Expr::concat(expr, self.additive()?);
+        }
+        Ok(expr)
+    }
+
+    fn additive(&mut self) -> Result<Expr> {
+        let mut expr = self.multiplicative()?;
+        loop {
+            let op = if self.eat_punct("+") {
+                Operation::Add
+            } else if self.eat_punct("-") {
+                Operation::Sub
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::binop(op, expr, self.multiplicative()?);
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr> {
+        let mut expr = self.sqlite_concat()?;
+        loop {
+            let op = if self.eat_punct("*") {
+                Operation::Mul
+            } else if self.eat_punct("/") {
+                Operation::Div
+            } else if self.eat_punct("%") {
+                Operation::Mod
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::binop(op, expr, self.sqlite_concat()?);
+        }
+    }
+
+    fn sqlite_concat(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.dialect == Dialect::Sqlite && self.eat_punct("||") {
+            expr = Expr::concat(expr, self.unary()?);
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat_punct("-") {
+            return Ok(match self.unary()? {
+                Expr::Literal(Literal::Int(i)) => Expr::Literal(Literal::Int(-i)),
+                Expr::Literal(Literal::Float(f)) => Expr::Literal(Literal::Float(-f)),
+                expr => Expr::Neg(Box::new(expr)),
+            });
+        }
+        if self.eat_punct("+") {
+            return self.unary();
+        }
+        let mut expr = self.primary()?;
+        while self.dialect == Dialect::Postgres && self.eat_punct("::") {
+            expr = Expr::cast(expr, self.cast_type()?);
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let Some(t) = self.peek().cloned() else {
+            return Err(self.unexpected("an expression"));
+        };
+        let next_is_adjacent_word = self
+            .tokens
+            .get(self.pos + 1)
+            .is_some_and(|n| n.kind == Kind::Word && !n.space);
+        match t.kind {
+            Kind::Punct if t.text == "(" => {
+                self.pos += 1;
+                let expr = if self.is_query_start() {
+                    Expr::subquery(self.select()?)
+                } else {
+                    let expr = self.expr()?;
+                    if self.is_punct(",") {
+                        let mut row = vec![expr];
+                        while self.eat_punct(",") {
+                            row.push(self.expr()?);
+                        }
+                        Expr::Row(row)
+                    } else {
+                        expr
+                    }
+                };
+                self.expect_punct(")")?;
+                Ok(expr)
+            }
+            Kind::Punct if t.text == "." && next_is_adjacent_word => self.number(),
+            // Placeholders are kept as written.
+            Kind::Punct if t.text == "?" => {
+                self.pos += 1;
+                Ok(Expr::Raw(t.text))
+            }
+            Kind::Punct if t.text == ":" && next_is_adjacent_word => {
+                self.pos += 2;
+                Ok(Expr::Raw(self.text(self.pos - 2)))
+            }
+            Kind::Word if t.text.starts_with('$') => {
+                self.pos += 1;
+                Ok(Expr::Raw(t.text))
+            }
+            Kind::Word if t.text.starts_with(|c: char| c.is_ascii_digit()) => self.number(),
+            Kind::Quoted if !self.is_ident() => {
+                let literal = self.string()?;
+                Ok(Expr::Literal(literal))
+            }
+            Kind::Word if is_reserved(&t.text) => self.keyword_expr(),
+            // Typed literals, e.g. `DATE '2024-01-01'`
+            Kind::Word
+                if self
+                    .tokens
+                    .get(self.pos + 1)
+                    .is_some_and(|n| n.kind == Kind::Quoted && n.text.starts_with('\'')) =>
+            {
+                Err(self.unsupported("typed literal"))
+            }
+            _ => {
+                let word = t.text.clone();
+                let start = self.pos;
+                let mut path = vec![self.ident()?];
+                if path.len() == 1 && self.is_punct("(") && t.kind == Kind::Word {
+                    return Ok(self.function(word)?.into());
+                }
+                while self.eat_punct(".") {
+                    path.push(self.ident()?);
+                }
+                let column = path.pop().unwrap();
+                let table = path.pop();
+                let schema = path.pop();
+                if !path.is_empty() {
+                    bail!("Too many parts in column name {}", self.text(start));
+                }
+                Ok(Expr::Column {
+                    schema,
+                    table,
+                    column,
+                })
+            }
+        }
+    }
+
+    fn keyword_expr(&mut self) -> Result<Expr> {
+        let keyword = self.peek().unwrap().text.to_ascii_uppercase();
+        let is_call = self
+            .tokens
+            .get(self.pos + 1)
+            .is_some_and(|n| n.text == "(" && !n.space);
+        match keyword.as_str() {
+            "NULL" | "TRUE" | "FALSE" => {
+                self.pos += 1;
+                Ok(Expr::Literal(match keyword.as_str() {
+                    "NULL" => Literal::Null,
+                    b => Literal::Bool(b == "TRUE"),
+                }))
+            }
+            "CURRENT_TIMESTAMP" if !is_call => {
+                self.pos += 1;
+                Ok(Function::now().into())
+            }
+            "CURRENT_DATE" | "CURRENT_TIME" => {
+                self.pos += 1;
+                Ok(Expr::Raw(keyword))
+            }
+            "CASE" => self.case(),
+            "CAST" => {
+                self.pos += 1;
+                self.expect_punct("(")?;
+                let expr = self.expr()?;
+                self.expect_keyword("AS")?;
+                let typ = self.cast_type()?;
+                self.expect_punct(")")?;
+                Ok(Expr::cast(expr, typ))
+            }
+            "EXISTS" => {
+                self.pos += 1;
+                self.expect_punct("(")?;
+                let select = self.select()?;
+                self.expect_punct(")")?;
+                Ok(Expr::exists(select))
+            }
+            // Mysql functions that share a name with a keyword.
+            "LEFT" | "RIGHT" | "VALUES" if is_call => {
+                let name = self.peek().unwrap().text.clone();
+                self.pos += 1;
+                Ok(self.function(name)?.into())
+            }
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+
+    fn case(&mut self) -> Result<Expr> {
+        self.expect_keyword("CASE")?;
+        if !self.is_keyword("WHEN") {
+            return Err(self.unsupported("CASE operand"));
+        }
+        let mut case: Option<Case> = None;
+        while self.eat_keyword("WHEN") {
+            let condition = self.expr()?;
+            self.expect_keyword("THEN")?;
+            let value = self.expr()?;
+            case = Some(match case {
+                Some(case) => case.when(condition, value),
+                None => Case::new_when(condition, value),
+            });
+        }
+        let mut case = case.unwrap();
+        if self.eat_keyword("ELSE") {
+            case = case.els(self.expr()?);
+        }
+        self.expect_keyword("END")?;
+        Ok(Expr::case(case))
+    }
+
+    fn number(&mut self) -> Result<Expr> {
+        let start = self.pos;
+        // A decimal point splits a number into several tokens, e.g. `1.5` or `.5`, and so does
+        // the sign of an exponent, e.g. `1e-5`.
+        while let Some(t) = self.peek()
+            && (self.pos == start || !t.space)
+            && (t.kind == Kind::Word
+                || t.text == "."
+                || (matches!(t.text.as_str(), "-" | "+")
+                    && self.tokens[self.pos - 1].text.ends_with(['e', 'E'])))
+        {
+            self.pos += 1;
+        }
+        let text = self.text(start);
+        if let Ok(i) = text.parse::<i64>() {
+            Ok(Expr::Literal(Literal::Int(i)))
+        } else if let Ok(f) = text.parse::<f64>() {
+            // Keep numbers that don't survive a round trip through f64 as written, e.g.
+            // `12345678901234567.89` or `1e400`.
+            if f.is_finite() && format!("{:?}", f) == text {
+                Ok(Expr::Literal(Literal::Float(f)))
+            } else {
+                Ok(Expr::Raw(text))
+            }
+        } else {
+            self.pos = start;
+            Err(self.unsupported("number"))
+        }
+    }
+
+    fn string(&mut self) -> Result<Literal> {
+        let text = self.peek().unwrap().text.clone();
+        let backslash = self.dialect == Dialect::Mysql;
+        let literal = match text.chars().next().unwrap() {
+            '\'' => quoted(&text, '\'').map(|s| Literal::String(unescape(s, '\'', backslash))),
+            '"' if self.dialect == Dialect::Mysql => {
+                quoted(&text, '"').map(|s| Literal::String(unescape(s, '"', true)))
+            }
+            'E' | 'e' => quoted(&text[1..], '\'').map(|s| Literal::String(unescape(s, '\'', true))),
+            'X' | 'x' => quoted(&text[1..], '\'').and_then(|hex| {
+                (0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+                    .collect::<Option<Vec<u8>>>()
+                    .map(Literal::Bytes)
+            }),
+            '$' => {
+                let tag_end = text[1..].find('$').unwrap() + 2;
+                Some(Literal::String(
+                    text[tag_end..text.len() - tag_end].to_string(),
+                ))
+            }
+            _ => None,
+        };
+        match literal {
+            Some(literal) => {
+                self.pos += 1;
+                Ok(literal)
+            }
+            None => Err(self.unsupported("string literal")),
+        }
+    }
+
+    fn function(&mut self, name: String) -> Result<Function> {
+        self.expect_punct("(")?;
+        let mut function = Function::new(&name, vec![]);
+        if self.eat_punct("*") {
+            function.args.push(Expr::Raw("*".to_string()));
+        } else if !self.is_punct(")") {
+            function.distinct = self.eat_keyword("DISTINCT");
+            function.args = self.expr_list()?;
+            if self.eat_keywords(&["ORDER", "BY"]) {
+                function.order = self.order_list()?;
+            }
+        }
+        self.expect_punct(")")?;
+        if self.eat_keyword("FILTER") {
+            self.expect_punct("(")?;
+            self.expect_keyword("WHERE")?;
+            function = function.filter(self.expr()?);
+            self.expect_punct(")")?;
+        }
+        if self.eat_keyword("OVER") {
+            let over = if self.eat_punct("(") {
+                let over = self.over()?;
+                self.expect_punct(")")?;
+                over
+            } else {
+                Over::window(&self.ident()?)
+            };
+            function = function.over(over);
+        }
+        Ok(function)
+    }
+
+    /// A window specification, without the surrounding parentheses.
+    fn over(&mut self) -> Result<Over> {
+        let mut over = Over::new();
+        let keywords = ["PARTITION", "ORDER", "ROWS", "RANGE", "GROUPS"];
+        if self.is_ident() && !keywords.iter().any(|k| self.is_keyword(k)) {
+            over.window = Some(self.ident()?);
+        }
+        if self.eat_keywords(&["PARTITION", "BY"]) {
+            over.partition = self.expr_list()?;
+        }
+        if self.eat_keywords(&["ORDER", "BY"]) {
+            over.order = self.order_list()?;
+        }
+        let unit = if self.eat_keyword("ROWS") {
+            FrameUnit::Rows
+        } else if self.eat_keyword("RANGE") {
+            FrameUnit::Range
+        } else if self.eat_keyword("GROUPS") {
+            FrameUnit::Groups
+        } else {
+            return Ok(over);
+        };
+        over.frame = Some(if self.eat_keyword("BETWEEN") {
+            let start = self.frame_bound()?;
+            self.expect_keyword("AND")?;
+            Frame {
+                unit,
+                start,
+                end: Some(self.frame_bound()?),
+            }
+        } else {
+            Frame {
+                unit,
+                start: self.frame_bound()?,
+                end: None,
+            }
+        });
+        Ok(over)
+    }
+
+    fn frame_bound(&mut self) -> Result<FrameBound> {
+        if self.eat_keywords(&["UNBOUNDED", "PRECEDING"]) {
+            return Ok(FrameBound::UnboundedPreceding);
+        }
+        if self.eat_keywords(&["UNBOUNDED", "FOLLOWING"]) {
+            return Ok(FrameBound::UnboundedFollowing);
+        }
+        if self.eat_keywords(&["CURRENT", "ROW"]) {
+            return Ok(FrameBound::CurrentRow);
+        }
+        let offset = self.concat()?;
+        if self.eat_keyword("PRECEDING") {
+            Ok(FrameBound::preceding(offset))
+        } else if self.eat_keyword("FOLLOWING") {
+            Ok(FrameBound::following(offset))
+        } else {
+            Err(self.unexpected("PRECEDING or FOLLOWING"))
+        }
+    }
+
+    fn order_list(&mut self) -> Result<Vec<OrderBy>> {
+        let mut order = vec![self.order_by()?];
+        while self.eat_punct(",") {
+            order.push(self.order_by()?);
+        }
+        Ok(order)
+    }
+
+    fn order_by(&mut self) -> Result<OrderBy> {
+        let start = self.pos;
+        // Parse the expression to find where it ends, but keep it as written.
+        if self.expr().is_err() || !self.at_boundary() {
+            self.pos = start;
+            self.raw_until(&[])?;
+        }
+        let mut order = OrderBy::new(&self.text(start));
+        if self.eat_keyword("ASC") {
+            order = order.asc();
+        } else if self.eat_keyword("DESC") {
+            order = order.desc();
+        }
+        if self.eat_keywords(&["NULLS", "FIRST"]) {
+            order = order.nulls_first();
+        } else if self.eat_keywords(&["NULLS", "LAST"]) {
+            order = order.nulls_last();
+        }
+        Ok(order)
+    }
+
+    /// A type name, e.g. `numeric(10, 2)`, `timestamp with time zone` or `text[]`.
+    fn typ(&mut self) -> Result<Type> {
+        let start = self.pos;
+        let Some(t) = self.peek().filter(|t| t.kind == Kind::Word) else {
+            return Err(self.unexpected("a type"));
+        };
+        let mut name = t.text.to_lowercase();
+        self.pos += 1;
+        match name.as_str() {
+            "double" if self.eat_keyword("PRECISION") => name.push_str(" precision"),
+            "character" | "char" if self.eat_keyword("VARYING") => name.push_str(" varying"),
+            "timestamp" | "time" => {
+                if self.eat_keywords(&["WITH", "TIME", "ZONE"]) {
+                    name.push_str(" with time zone");
+                } else if self.eat_keywords(&["WITHOUT", "TIME", "ZONE"]) {
+                    name.push_str(" without time zone");
+                }
+            }
+            _ => {}
+        }
+        let mut args = vec![];
+        if self.eat_punct("(") {
+            args.push(self.usize()?);
+            while self.eat_punct(",") {
+                args.push(self.usize()?);
+            }
+            self.expect_punct(")")?;
+        }
+        let mut typ = match (name.as_str(), args.as_slice()) {
+            ("numeric" | "decimal", &[p, s]) => Type::Numeric(p as u8, s as u8),
+            ("numeric" | "decimal", &[p]) => Type::Numeric(p as u8, 0),
+            _ => match Type::from_str(&name)? {
+                // Keep the type as written, including any modifiers.
+                Type::Other(_) => Type::Other(self.text(start)),
+                typ => typ,
+            },
+        };
+        while self.eat_punct("[") {
+            let _ = self.usize();
+            self.expect_punct("]")?;
+            typ = Type::Array(Box::new(typ));
+        }
+        Ok(typ)
+    }
+
+    /// The type of a cast, kept as written if it renders differently, e.g. `text`, which is
+    /// `Type::Text` but renders as `character varying` on Postgres.
+    fn cast_type(&mut self) -> Result<Type> {
+        let start = self.pos;
+        let typ = self.typ()?;
+        let text = self.text(start);
+        if typ.to_sql(self.dialect).eq_ignore_ascii_case(&text) {
+            Ok(typ)
+        } else {
+            Ok(Type::Other(text))
+        }
+    }
+
+    fn is_query_start_at(&self, n: usize) -> bool {
+        self.is_keyword_at(n, "SELECT") || self.is_keyword_at(n, "WITH")
+    }
+
+    fn is_query_start(&self) -> bool {
+        self.is_query_start_at(0)
+    }
+
+    fn select(&mut self) -> Result<Select> {
+        match self.query()? {
+            Query::Select(select) => Ok(select),
+            Query::Compound(_) => Err(self.unsupported("compound query in expression")),
+        }
+    }
+
+    /// A SELECT, or a compound of them, with its CTEs and trailing clauses.
+    fn query(&mut self) -> Result<Query> {
+        let ctes = self.ctes()?;
+        let first = self.query_operand()?;
+        let mut rest = vec![];
+        loop {
+            let operator = if self.eat_keyword("UNION") {
+                SetOperator::Union
+            } else if self.eat_keyword("INTERSECT") {
+                SetOperator::Intersect
+            } else if self.eat_keyword("EXCEPT") {
+                SetOperator::Except
+            } else {
+                break;
+            };
+            let all = self.eat_keyword("ALL");
+            if !all {
+                self.eat_keyword("DISTINCT");
+            }
+            rest.push((SetOperation { operator, all }, self.query_operand()?));
+        }
+        let order = match self.eat_keywords(&["ORDER", "BY"]) {
+            true => self.order_list()?,
+            false => vec![],
+        };
+        let (limit, offset) = self.limit_offset()?;
+        let locks = self.locks()?;
+        if rest.is_empty() {
+            let Query::Select(mut select) = first else {
+                if !ctes.is_empty() || !locks.is_empty() {
+                    bail!("WITH and locking clauses are not supported on compound queries");
+                }
+                return Ok(first);
+            };
+            if !ctes.is_empty() {
+                if !select.ctes.is_empty() {
+                    bail!("A parenthesized query can't have two WITH clauses");
+                }
+                select.ctes = ctes;
+            }
+            if !order.is_empty() {
+                select.order = order;
+            }
+            select.limit = limit.or(select.limit);
+            select.offset = offset.or(select.offset);
+            select.locks.extend(locks);
+            return Ok(Query::Select(select));
+        }
+        if !ctes.is_empty() || !locks.is_empty() {
+            bail!("WITH and locking clauses are not supported on compound queries");
+        }
+        Ok(Query::Compound(Compound {
+            first: first.into_compound_query(),
+            rest: rest
+                .into_iter()
+                .map(|(op, q)| (op, q.into_compound_query()))
+                .collect(),
+            order,
+            limit,
+            offset,
+        }))
+    }
+
+    fn query_operand(&mut self) -> Result<Query> {
+        if self.eat_punct("(") {
+            let query = self.query()?;
+            self.expect_punct(")")?;
+            return Ok(query);
+        }
+        self.select_core().map(Query::Select)
+    }
+
+    fn ctes(&mut self) -> Result<Vec<Cte>> {
+        let mut ctes = vec![];
+        if !self.eat_keyword("WITH") {
+            return Ok(ctes);
+        }
+        let recursive = self.eat_keyword("RECURSIVE");
+        loop {
+            let mut cte = Cte::new(self.ident()?, CteQuery::Raw(String::new()));
+            cte.recursive = recursive;
+            if self.is_punct("(") {
+                cte.columns = self.ident_list()?;
+            }
+            self.expect_keyword("AS")?;
+            if self.eat_keywords(&["NOT", "MATERIALIZED"]) {
+                cte = cte.not_materialized();
+            } else if self.eat_keyword("MATERIALIZED") {
+                cte = cte.materialized();
+            }
+            self.expect_punct("(")?;
+            cte.query = self.cte_query()?;
+            self.expect_punct(")")?;
+            if self.eat_keyword("SEARCH") {
+                let depth_first = self.eat_keyword("DEPTH");
+                if !depth_first {
+                    self.expect_keyword("BREADTH")?;
+                }
+                self.expect_keywords(&["FIRST", "BY"])?;
+                let mut by = vec![self.ident()?];
+                while self.eat_punct(",") {
+                    by.push(self.ident()?);
+                }
+                let by: Vec<&str> = by.iter().map(String::as_str).collect();
+                self.expect_keyword("SET")?;
+                let set = self.ident()?;
+                cte = match depth_first {
+                    true => cte.search_depth_first(&by, &set),
+                    false => cte.search_breadth_first(&by, &set),
+                };
+            }
+            if self.eat_keyword("CYCLE") {
+                let mut columns = vec![self.ident()?];
+                while self.eat_punct(",") {
+                    columns.push(self.ident()?);
+                }
+                let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+                self.expect_keyword("SET")?;
+                let set = self.ident()?;
+                self.expect_keyword("USING")?;
+                let using = self.ident()?;
+                cte = cte.cycle(&columns, &set, &using);
+            }
+            ctes.push(cte);
+            if !self.eat_punct(",") {
+                return Ok(ctes);
+            }
+        }
+    }
+
+    /// The body of a CTE, or its text if it can't be parsed.
+    fn cte_query(&mut self) -> Result<CteQuery> {
+        let start = self.pos;
+        let query = if self.is_keyword("INSERT") {
+            self.insert().map(CteQuery::Insert)
+        } else if self.is_keyword("UPDATE") {
+            self.update().map(CteQuery::Update)
+        } else if self.is_keyword("DELETE") {
+            self.delete().map(CteQuery::Delete)
+        } else {
+            self.query().map(|query| match query {
+                Query::Select(select) => CteQuery::Select(select),
+                Query::Compound(compound) => CteQuery::Compound(compound),
+            })
+        };
+        match query {
+            Ok(query) if self.is_punct(")") => Ok(query),
+            _ => {
+                self.pos = start;
+                self.raw_group().map(CteQuery::Raw)
+            }
+        }
+    }
+
+    /// A SELECT without CTEs, ORDER BY, LIMIT and locking clauses, which may belong to a
+    /// compound query instead.
+    fn select_core(&mut self) -> Result<Select> {
+        self.expect_keyword("SELECT")?;
+        let mut select = Select::default();
+        if self.eat_keyword("DISTINCT") {
+            if self.eat_keyword("ON") {
+                self.expect_punct("(")?;
+                select.distinct = Distinct::On(self.expr_list()?);
+                self.expect_punct(")")?;
+            } else {
+                select.distinct = Distinct::All;
+            }
+        } else {
+            self.eat_keyword("ALL");
+        }
+        select.columns.push(self.select_column()?);
+        while self.eat_punct(",") {
+            select.columns.push(self.select_column()?);
+        }
+        if self.eat_keyword("FROM") {
+            let (from, join) = self.sources()?;
+            select.from = Some(from);
+            select.join = join;
+        }
+        if self.eat_keyword("WHERE") {
+            select.where_ = self.condition()?;
+        }
+        if self.eat_keywords(&["GROUP", "BY"]) {
+            select.group = self.group_by()?;
+        }
+        if self.eat_keyword("HAVING") {
+            select.having = self.condition()?;
+        }
+        if self.eat_keyword("WINDOW") {
+            loop {
+                let name = self.ident()?;
+                self.expect_keyword("AS")?;
+                self.expect_punct("(")?;
+                let over = self.over()?;
+                self.expect_punct(")")?;
+                select.windows.push(Window { name, over });
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+        }
+        Ok(select)
+    }
+
+    fn select_column(&mut self) -> Result<SelectColumn> {
+        let start = self.pos;
+        // `*` and `t.*`
+        if self.eat_punct("*")
+            || (self.is_ident() && self.punct_len(".") == Some(1) && {
+                self.pos += 2;
+                self.eat_punct("*")
+            })
+        {
+            return Ok(SelectColumn::raw(&self.text(start)));
+        }
+        self.pos = start;
+        let mut column = match self.expr_or_raw()? {
+            Expr::Column {
+                schema,
+                table,
+                column,
+            } => SelectColumn {
+                expression: SelectExpression::Column {
+                    schema,
+                    table,
+                    column,
+                },
+                alias: None,
+            },
+            Expr::Raw(raw) => SelectColumn::raw(&raw),
+            expr => SelectColumn::expr(expr),
+        };
+        if let Some(alias) = self.alias()? {
+            column = column.alias(alias);
+        }
+        Ok(column)
+    }
+
+    /// A condition in WHERE, HAVING or ON, as a list of terms joined with AND.
+    ///
+    /// If part of the condition isn't supported, the terms joined with AND are kept separately,
+    /// so the supported ones can still be inspected.
+    fn condition(&mut self) -> Result<Where> {
+        let start = self.pos;
+        if let Ok(expr) = self.expr()
+            && self.at_boundary()
+        {
+            return match condition(expr) {
+                where_ @ Where::And(_) => Ok(where_),
+                where_ => Ok(Where::And(vec![where_])),
+            };
+        }
+        self.pos = start;
+        let mut terms = vec![];
+        loop {
+            let term = self.pos;
+            match self.not() {
+                Ok(expr) if self.at_boundary() || self.is_keyword("AND") => {
+                    terms.push(condition(expr))
+                }
+                _ => {
+                    self.pos = term;
+                    let raw = self.raw_until(&["AND", "OR"])?;
+                    if self.is_keyword("OR") {
+                        // Keep the whole condition, grouped so that terms added later apply to
+                        // all of it.
+                        self.pos = start;
+                        let raw = format!("({})", self.raw_until(&[])?);
+                        return Ok(Where::And(vec![Where::Expr(Expr::Raw(raw))]));
+                    }
+                    terms.push(Where::Expr(Expr::Raw(raw)));
+                }
+            }
+            if !self.eat_keyword("AND") {
+                return Ok(Where::And(terms));
+            }
+        }
+    }
+
+    fn group_by(&mut self) -> Result<Vec<GroupBy>> {
+        let mut group = vec![];
+        loop {
+            let item = if self.is_keyword("ROLLUP") && self.is_punct_at(1, "(") {
+                self.pos += 2;
+                let exprs = self.expr_list()?;
+                self.expect_punct(")")?;
+                GroupBy::Rollup(exprs)
+            } else if self.is_keyword("CUBE") && self.is_punct_at(1, "(") {
+                self.pos += 2;
+                let exprs = self.expr_list()?;
+                self.expect_punct(")")?;
+                GroupBy::Cube(exprs)
+            } else if self.eat_keywords(&["GROUPING", "SETS"]) {
+                self.expect_punct("(")?;
+                let mut sets = vec![];
+                loop {
+                    if self.eat_punct("(") {
+                        let set = match self.is_punct(")") {
+                            true => vec![],
+                            false => self.expr_list()?,
+                        };
+                        self.expect_punct(")")?;
+                        sets.push(set);
+                    } else {
+                        sets.push(vec![self.expr()?]);
+                    }
+                    if !self.eat_punct(",") {
+                        break;
+                    }
+                }
+                self.expect_punct(")")?;
+                GroupBy::GroupingSets(sets)
+            } else {
+                match self.expr_or_raw()? {
+                    Expr::Literal(Literal::Int(n)) if n > 0 => GroupBy::Position(n as usize),
+                    expr => GroupBy::Expr(expr),
+                }
+            };
+            group.push(item);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        // Mysql
+        if self.eat_keywords(&["WITH", "ROLLUP"]) {
+            let exprs = group
+                .into_iter()
+                .map(|g| match g {
+                    GroupBy::Expr(expr) => Ok(expr),
+                    GroupBy::Position(n) => Ok(Expr::Raw(n.to_string())),
+                    _ => bail!("WITH ROLLUP can only be applied to plain GROUP BY items"),
+                })
+                .collect::<Result<_>>()?;
+            group = vec![GroupBy::Rollup(exprs)];
+        }
+        Ok(group)
+    }
+
+    fn is_punct_at(&self, n: usize, p: &str) -> bool {
+        self.tokens
+            .get(self.pos + n)
+            .is_some_and(|t| t.kind == Kind::Punct && t.text == p)
+    }
+
+    fn limit_offset(&mut self) -> Result<(Option<usize>, Option<usize>)> {
+        let (mut limit, mut offset) = (None, None);
+        loop {
+            if self.eat_keyword("LIMIT") {
+                if self.eat_keyword("ALL") {
+                    continue;
+                }
+                let n = self.usize()?;
+                // Mysql and Sqlite: `LIMIT offset, count`
+                if self.eat_punct(",") {
+                    offset = Some(n);
+                    limit = Some(self.usize()?);
+                } else {
+                    limit = Some(n);
+                }
+            } else if self.eat_keyword("OFFSET") {
+                offset = Some(self.usize()?);
+                let _ = self.eat_keyword("ROWS") || self.eat_keyword("ROW");
+            } else if self.eat_keyword("FETCH") {
+                if !self.eat_keyword("FIRST") {
+                    self.expect_keyword("NEXT")?;
+                }
+                limit = Some(self.usize().unwrap_or(1));
+                if !self.eat_keyword("ROWS") {
+                    self.expect_keyword("ROW")?;
+                }
+                self.expect_keyword("ONLY")?;
+            } else {
+                return Ok((limit, offset));
+            }
+        }
+    }
+
+    fn locks(&mut self) -> Result<Vec<Lock>> {
+        let mut locks = vec![];
+        loop {
+            // Mysql
+            if self.eat_keywords(&["LOCK", "IN", "SHARE", "MODE"]) {
+                locks.push(Lock::share());
+                continue;
+            }
+            if !self.eat_keyword("FOR") {
+                return Ok(locks);
+            }
+            let mut lock = if self.eat_keyword("UPDATE") {
+                Lock::update()
+            } else if self.eat_keywords(&["NO", "KEY", "UPDATE"]) {
+                Lock::no_key_update()
+            } else if self.eat_keyword("SHARE") {
+                Lock::share()
+            } else if self.eat_keywords(&["KEY", "SHARE"]) {
+                Lock::key_share()
+            } else {
+                return Err(self.unexpected("UPDATE or SHARE"));
+            };
+            if self.eat_keyword("OF") {
+                lock = lock.of(&self.ident()?);
+                while self.eat_punct(",") {
+                    lock = lock.of(&self.ident()?);
+                }
+            }
+            if self.eat_keyword("NOWAIT") {
+                lock = lock.nowait();
+            } else if self.eat_keywords(&["SKIP", "LOCKED"]) {
+                lock = lock.skip_locked();
+            }
+            locks.push(lock);
+        }
+    }
+
+    /// The FROM clause. Sources after the first are joins; a comma is a CROSS JOIN.
+    fn sources(&mut self) -> Result<(From, Vec<Join>)> {
+        let from = self.source()?.into_from()?;
+        Ok((from, self.joins()?))
+    }
+
+    fn joins(&mut self) -> Result<Vec<Join>> {
+        use JoinType::*;
+        let mut joins = vec![];
+        loop {
+            let comma = self.eat_punct(",");
+            let natural = !comma && self.eat_keyword("NATURAL");
+            let typ = if comma || self.eat_keywords(&["CROSS", "JOIN"]) {
+                Cross
+            } else if self.eat_keyword("JOIN") || self.eat_keywords(&["INNER", "JOIN"]) {
+                Inner
+            } else if self.eat_keyword("LEFT") {
+                self.eat_keyword("OUTER");
+                self.expect_keyword("JOIN")?;
+                Left
+            } else if self.eat_keyword("RIGHT") {
+                self.eat_keyword("OUTER");
+                self.expect_keyword("JOIN")?;
+                Right
+            } else if self.eat_keyword("FULL") {
+                self.eat_keyword("OUTER");
+                self.expect_keyword("JOIN")?;
+                Full
+            } else if natural {
+                return Err(self.unexpected("JOIN"));
+            } else {
+                return Ok(joins);
+            };
+            let mut join = self.source()?.into_join(typ);
+            if natural {
+                join.criteria = Criteria::Natural;
+            } else if typ != Cross {
+                if self.eat_keyword("ON") {
+                    join.criteria = Criteria::On(self.condition()?);
+                } else if self.eat_keyword("USING") {
+                    join.criteria = Criteria::Using(self.ident_list()?);
+                }
+            }
+            joins.push(join);
+        }
+    }
+
+    fn source(&mut self) -> Result<Source> {
+        let lateral = self.eat_keyword("LATERAL");
+        let table = if self.eat_punct("(") {
+            let table = if self.eat_keyword("VALUES") {
+                JoinTable::Values(self.rows()?)
+            } else {
+                match self.query()? {
+                    Query::Select(select) => JoinTable::Select(select),
+                    Query::Compound(compound) => JoinTable::Compound(compound),
+                }
+            };
+            self.expect_punct(")")?;
+            table
+        } else if self.peek().is_some_and(|t| t.kind == Kind::Word)
+            && self.is_ident()
+            && self.is_punct_at(1, "(")
+        {
+            let name = self.peek().unwrap().text.clone();
+            self.pos += 1;
+            JoinTable::Function(self.function(name)?)
+        } else {
+            let (schema, table) = self.table_name()?;
+            JoinTable::Table { schema, table }
+        };
+        let alias = self.alias()?;
+        let columns = match alias.is_some() && self.is_punct("(") {
+            true => self.ident_list()?,
+            false => vec![],
+        };
+        Ok(Source {
+            table,
+            alias,
+            columns,
+            lateral,
+        })
+    }
+
+    /// The rows of a VALUES list.
+    fn rows(&mut self) -> Result<Vec<Value>> {
+        let mut rows = vec![];
+        loop {
+            // Mysql spells rows as `ROW(...)` in some contexts.
+            self.eat_keyword("ROW");
+            self.expect_punct("(")?;
+            let mut row = vec![self.expr_or_raw()?];
+            while self.eat_punct(",") {
+                row.push(self.expr_or_raw()?);
+            }
+            self.expect_punct(")")?;
+            rows.push(Value::from(row));
+            if !self.eat_punct(",") {
+                return Ok(rows);
+            }
+        }
+    }
+
+    /// `col = value, ...`, as used by ON CONFLICT DO UPDATE and ON DUPLICATE KEY UPDATE.
+    fn updates(&mut self) -> Result<Vec<(String, Expr)>> {
+        let mut updates = vec![];
+        loop {
+            let column = self.ident()?;
+            self.expect_punct("=")?;
+            updates.push((column, self.expr_or_raw()?));
+            if !self.eat_punct(",") {
+                return Ok(updates);
+            }
+        }
+    }
+
+    /// RETURNING for INSERT and DELETE, which only support column names.
+    fn returning_columns(&mut self) -> Result<Vec<String>> {
+        let mut columns = vec![];
+        loop {
+            if self.eat_punct("*") {
+                columns.push("*".to_string());
+            } else {
+                columns.push(self.ident()?);
+            }
+            if !self.eat_punct(",") {
+                return Ok(columns);
+            }
+        }
+    }
+
+    fn insert(&mut self) -> Result<Insert> {
+        let mut on_conflict = OnConflict::Abort;
+        if self.eat_keyword("REPLACE") {
+            on_conflict = OnConflict::Replace;
+        } else {
+            self.expect_keyword("INSERT")?;
+            if self.eat_keyword("OR") {
+                on_conflict = if self.eat_keyword("IGNORE") {
+                    OnConflict::Ignore
+                } else if self.eat_keyword("REPLACE") {
+                    OnConflict::Replace
+                } else if self.eat_keyword("ABORT") {
+                    OnConflict::Abort
+                } else {
+                    return Err(self.unsupported("conflict resolution"));
+                };
+            } else if self.eat_keyword("IGNORE") {
+                on_conflict = OnConflict::Ignore;
+            }
+        }
+        self.expect_keyword("INTO")?;
+        let (schema, table) = self.table_name()?;
+        let mut insert = Insert::new(&table);
+        insert.schema = schema;
+        if self.is_punct("(") && !self.is_query_start_at(1) {
+            insert.columns = self.ident_list()?;
+        }
+        insert.values = if self.eat_keywords(&["DEFAULT", "VALUES"]) {
+            Values::DefaultValues
+        } else if self.eat_keyword("VALUES") || self.eat_keyword("VALUE") {
+            Values::Values(self.rows()?)
+        } else {
+            match self.query()? {
+                Query::Select(select) => Values::Select(select),
+                Query::Compound(compound) => Values::Compound(compound),
+            }
+        };
+        if self.dialect == Dialect::Mysql && self.eat_keyword("AS") {
+            insert.row_alias = Some(self.ident()?);
+        }
+        if self.eat_keywords(&["ON", "CONFLICT"]) {
+            let conflict = if self.is_punct("(") {
+                Conflict::Columns(self.ident_list()?)
+            } else if self.eat_keywords(&["ON", "CONSTRAINT"]) {
+                Conflict::ConstraintName(self.ident()?)
+            } else {
+                Conflict::NoTarget
+            };
+            self.expect_keyword("DO")?;
+            on_conflict = if self.eat_keyword("NOTHING") {
+                OnConflict::Ignore
+            } else {
+                self.expect_keywords(&["UPDATE", "SET"])?;
+                let updates = self.updates()?;
+                let where_ = match self.eat_keyword("WHERE") {
+                    true => self.condition()?,
+                    false => Where::And(vec![]),
+                };
+                OnConflict::DoUpdate {
+                    conflict,
+                    updates,
+                    where_,
+                }
+            };
+        } else if self.eat_keywords(&["ON", "DUPLICATE", "KEY", "UPDATE"]) {
+            on_conflict = OnConflict::DoUpdate {
+                conflict: Conflict::NoTarget,
+                updates: self.updates()?,
+                where_: Where::And(vec![]),
+            };
+        }
+        insert.on_conflict = on_conflict;
+        if self.eat_keyword("RETURNING") {
+            insert.returning = self.returning_columns()?;
+        }
+        Ok(insert)
+    }
+
+    fn update(&mut self) -> Result<Update> {
+        let ctes = self.ctes()?;
+        self.expect_keyword("UPDATE")?;
+        let (schema, table) = self.table_name()?;
+        let mut update = Update::new(&table);
+        update.ctes = ctes;
+        update.schema = schema;
+        update.alias = self.alias()?;
+        // Mysql multi-table update
+        update.join = self.joins()?;
+        self.expect_keyword("SET")?;
+        loop {
+            let assignment = if self.is_punct("(") {
+                let columns = self.ident_list()?;
+                self.expect_punct("=")?;
+                Assignment::Row(columns, self.expr_or_raw()?)
+            } else {
+                let column = self.ident()?;
+                if self.is_punct(".") {
+                    return Err(self.unsupported("qualified column"));
+                }
+                self.expect_punct("=")?;
+                Assignment::Column(column, self.expr_or_raw()?)
+            };
+            update.values.push(assignment);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        if self.eat_keyword("FROM") {
+            if !update.join.is_empty() {
+                return Err(self.unsupported("FROM in multi-table UPDATE"));
+            }
+            let (from, join) = self.sources()?;
+            update.from = vec![from];
+            update.join = join;
+        }
+        if self.eat_keyword("WHERE") {
+            update.where_ = self.condition()?;
+        }
+        if self.eat_keyword("RETURNING") {
+            update.returning.push(self.select_column()?);
+            while self.eat_punct(",") {
+                update.returning.push(self.select_column()?);
+            }
+        }
+        if self.eat_keywords(&["ORDER", "BY"]) {
+            update.order = self.order_list()?;
+        }
+        if self.eat_keyword("LIMIT") {
+            update.limit = Some(self.usize()?);
+        }
+        Ok(update)
+    }
+
+    fn delete(&mut self) -> Result<Delete> {
+        let ctes = self.ctes()?;
+        self.expect_keyword("DELETE")?;
+        // Mysql multi-table delete: `DELETE a, b FROM a JOIN b ...`
+        let mut targets = vec![];
+        while !self.is_keyword("FROM") {
+            targets.push(self.ident()?);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_keyword("FROM")?;
+        let (schema, table) = self.table_name()?;
+        let mut delete = Delete::new(&table);
+        delete.ctes = ctes;
+        delete.schema = schema;
+        delete.alias = self.alias()?;
+        if !targets.is_empty() {
+            let primary = delete.alias.as_ref().unwrap_or(&delete.table);
+            let Some(i) = targets.iter().position(|t| t == primary) else {
+                bail!(
+                    "A multi-table DELETE must delete from its first table, {}",
+                    primary
+                );
+            };
+            targets.remove(i);
+            delete.targets = targets;
+            delete.join = self.joins()?;
+        }
+        if self.eat_keyword("USING") {
+            let (using, join) = self.sources()?;
+            delete.using = vec![using];
+            delete.join = join;
+        }
+        if self.eat_keyword("WHERE") {
+            delete.where_ = self.condition()?;
+        }
+        if self.eat_keyword("RETURNING") {
+            delete.returning = self.returning_columns()?;
+        }
+        if self.eat_keywords(&["ORDER", "BY"]) {
+            delete.order = self.order_list()?;
+        }
+        if self.eat_keyword("LIMIT") {
+            delete.limit = Some(self.usize()?);
+        }
+        Ok(delete)
+    }
+
+    fn create_table(&mut self) -> Result<Table> {
+        self.expect_keywords(&["CREATE", "TABLE"])?;
+        self.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let (schema, name) = self.table_name()?;
+        let mut table = Table::new(&name);
+        table.schema = schema;
+        self.expect_punct("(")?;
+        loop {
+            let constraint = ["CONSTRAINT", "PRIMARY", "FOREIGN", "UNIQUE", "CHECK"]
+                .iter()
+                .any(|k| self.is_keyword(k));
+            if constraint {
+                self.table_constraint(&mut table)?;
+            } else {
+                table.columns.push(self.column()?);
+            }
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct(")")?;
+        while self.peek().is_some_and(|t| t.text != ";") {
+            self.pos += 1;
+        }
+        Ok(table)
+    }
+
+    fn column(&mut self) -> Result<Column> {
+        let mut column = Column {
+            name: self.ident()?,
+            typ: self.typ()?,
+            nullable: true,
+            primary_key: false,
+            default: None,
+            constraint: None,
+            generated: None,
+        };
+        loop {
+            if self.eat_keyword("CONSTRAINT") {
+                self.ident()?;
+            } else if self.eat_keywords(&["NOT", "NULL"]) {
+                column.nullable = false;
+            } else if self.eat_keyword("NULL") {
+                column.nullable = true;
+            } else if self.eat_keywords(&["PRIMARY", "KEY"]) {
+                column.primary_key = true;
+                column.nullable = false;
+            } else if self.eat_keyword("DEFAULT") {
+                let start = self.pos;
+                column.default = Some(match self.concat() {
+                    Ok(expr)
+                        if self.at_boundary()
+                            || COLUMN_CONSTRAINTS.iter().any(|k| self.is_keyword(k)) =>
+                    {
+                        expr
+                    }
+                    _ => {
+                        self.pos = start;
+                        Expr::Raw(self.raw_until(COLUMN_CONSTRAINTS)?)
+                    }
+                });
+            } else if self.eat_keyword("REFERENCES") {
+                column.constraint = Some(self.references()?);
+            } else if self.eat_keyword("GENERATED") {
+                column.generated = Some(self.generated()?);
+            } else if self.peek().is_none() || [",", ")", ";"].iter().any(|p| self.is_punct(p)) {
+                return Ok(column);
+            } else {
+                return Err(self.unsupported("column constraint"));
+            }
+        }
+    }
+
+    /// `REFERENCES table [(columns)]`, after the keyword.
+    fn references(&mut self) -> Result<Constraint> {
+        let (schema, table) = self.table_name()?;
+        let columns = match self.is_punct("(") {
+            true => self.ident_list()?,
+            false => vec![],
+        };
+        if self.is_keyword("ON") || self.is_keyword("MATCH") || self.is_keyword("DEFERRABLE") {
+            return Err(self.unsupported("foreign key option"));
+        }
+        Ok(Constraint::ForeignKey(ForeignKey {
+            schema,
+            table,
+            columns,
+        }))
+    }
+
+    /// `GENERATED ... AS ...`, after the keyword.
+    fn generated(&mut self) -> Result<Generated> {
+        let time = if self.eat_keyword("ALWAYS") {
+            GenerationTime::Always
+        } else {
+            self.expect_keywords(&["BY", "DEFAULT"])?;
+            GenerationTime::ByDefault
+        };
+        self.expect_keyword("AS")?;
+        let value = if self.eat_keyword("IDENTITY") {
+            GenerationValue::Identity
+        } else {
+            self.expect_punct("(")?;
+            let expr = self.expr()?;
+            self.expect_punct(")")?;
+            self.expect_keyword("STORED")?;
+            GenerationValue::Expr(expr)
+        };
+        Ok(Generated { time, value })
+    }
+
+    fn table_constraint(&mut self, table: &mut Table) -> Result<()> {
+        if self.eat_keyword("CONSTRAINT") {
+            self.ident()?;
+        }
+        let primary_key = self.eat_keywords(&["PRIMARY", "KEY"]);
+        if !primary_key && !self.eat_keywords(&["FOREIGN", "KEY"]) {
+            return Err(self.unsupported("table constraint"));
+        }
+        let line = self.peek().map(|t| self.line(t.span.start)).unwrap_or(0);
+        let columns = self.ident_list()?;
+        if !primary_key && columns.len() > 1 {
+            bail!("Unsupported multi-column constraint at line {}", line);
+        }
+        for name in &columns {
+            let Some(column) = table.columns.iter_mut().find(|c| &c.name == name) else {
+                bail!("Constraint on unknown column {} at line {}", name, line);
+            };
+            if primary_key {
+                column.primary_key = true;
+                column.nullable = false;
+            } else {
+                self.expect_keyword("REFERENCES")?;
+                column.constraint = Some(self.references()?);
+            }
+        }
+        Ok(())
+    }
+
+    fn create_index(&mut self) -> Result<Index> {
+        self.expect_keyword("CREATE")?;
+        let unique = self.eat_keyword("UNIQUE");
+        self.expect_keyword("INDEX")?;
+        self.eat_keyword("CONCURRENTLY");
+        self.eat_keywords(&["IF", "NOT", "EXISTS"]);
+        let name = self.ident()?;
+        self.expect_keyword("ON")?;
+        self.eat_keyword("ONLY");
+        let (schema, table) = self.table_name()?;
+        let kind = match self.eat_keyword("USING") {
+            true => match self.ident()?.to_lowercase().as_str() {
+                "btree" => IndexKind::BTree,
+                "hash" => IndexKind::Hash,
+                "gist" => IndexKind::Gist,
+                "spgist" => IndexKind::SpGist,
+                "brin" => IndexKind::Brin,
+                other => IndexKind::Other(other.to_string()),
+            },
+            false => IndexKind::BTree,
+        };
+        let columns = self.ident_list()?;
+        if self.peek().is_some_and(|t| t.text != ";") {
+            return Err(self.unsupported("index option"));
+        }
+        Ok(Index {
+            name,
+            unique,
+            schema,
+            table,
+            columns,
+            kind,
+        })
+    }
+
+    fn alter_table(&mut self) -> Result<AlterTable> {
+        self.expect_keywords(&["ALTER", "TABLE"])?;
+        self.eat_keywords(&["IF", "EXISTS"]);
+        self.eat_keyword("ONLY");
+        let (schema, name) = self.table_name()?;
+        let mut actions = vec![];
+        loop {
+            actions.push(self.alter_action()?);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        Ok(AlterTable {
+            schema,
+            name,
+            actions,
+        })
+    }
+
+    fn alter_action(&mut self) -> Result<AlterAction> {
+        if self.eat_keywords(&["ADD", "CONSTRAINT"]) {
+            let name = self.ident()?;
+            self.expect_keywords(&["FOREIGN", "KEY"])?;
+            let line = self.peek().map(|t| self.line(t.span.start)).unwrap_or(0);
+            let columns = self.ident_list()?;
+            let [column] = columns.as_slice() else {
+                bail!("Unsupported multi-column constraint at line {}", line);
+            };
+            self.expect_keyword("REFERENCES")?;
+            return Ok(AlterAction::AddConstraint {
+                name,
+                column: column.clone(),
+                constraint: self.references()?,
+            });
+        }
+        if self.eat_keyword("ADD") {
+            self.eat_keyword("COLUMN");
+            return Ok(AlterAction::AddColumn {
+                column: self.column()?,
+            });
+        }
+        if self.eat_keyword("ALTER") {
+            self.eat_keyword("COLUMN");
+            let name = self.ident()?;
+            if self.eat_keywords(&["SET", "NOT", "NULL"]) {
+                return Ok(AlterAction::set_nullable(name, false));
+            }
+            if self.eat_keywords(&["DROP", "NOT", "NULL"]) {
+                return Ok(AlterAction::set_nullable(name, true));
+            }
+            self.eat_keywords(&["SET", "DATA"]);
+            self.expect_keyword("TYPE")?;
+            let typ = self.typ()?;
+            let using = match self.eat_keyword("USING") {
+                true => Some(self.raw_until(&[])?),
+                false => None,
+            };
+            return Ok(AlterAction::AlterColumn {
+                name,
+                action: AlterColumnAction::SetType { typ, using },
+            });
+        }
+        Err(self.unsupported("ALTER TABLE action"))
+    }
+
+    /// A statement in DDL for `Schema::try_from_ddl`, added to `schema`.
+    fn ddl_statement(&mut self, schema: &mut Schema) -> Result<()> {
+        if self.is_keywords(&["CREATE", "TABLE"]) {
+            schema.tables.push(self.create_table()?);
+        } else if self.is_keywords(&["CREATE", "INDEX"])
+            || self.is_keywords(&["CREATE", "UNIQUE", "INDEX"])
+        {
+            schema.indexes.push(self.create_index()?);
+        } else if self.is_keywords(&["ALTER", "TABLE"]) {
+            self.ddl_alter_table(schema)?;
+        } else {
+            let words = self.tokens.iter().take(2).map(|t| t.text.as_str());
+            bail!(
+                "Unsupported statement `{}`",
+                words.collect::<Vec<_>>().join(" ")
+            );
+        }
+        Ok(())
+    }
+
+    /// An ALTER TABLE statement, applied to a table created earlier.
+    fn ddl_alter_table(&mut self, schema: &mut Schema) -> Result<()> {
+        self.expect_keywords(&["ALTER", "TABLE"])?;
+        self.eat_keywords(&["IF", "EXISTS"]);
+        self.eat_keyword("ONLY");
+        let (schema_name, name) = self.table_name()?;
+        let Some(table) = schema.tables.iter_mut().find(|t| {
+            t.name == name
+                && (schema_name.is_none() || t.schema.is_none() || t.schema == schema_name)
+        }) else {
+            bail!("ALTER TABLE on unknown table {}", name);
+        };
+        loop {
+            let constraint = ["CONSTRAINT", "PRIMARY", "FOREIGN", "UNIQUE", "CHECK"]
+                .iter()
+                .any(|k| self.is_keyword_at(1, k));
+            if self.is_keyword("ADD") && constraint {
+                self.pos += 1;
+                self.table_constraint(table)?;
+            } else {
+                match self.alter_action()? {
+                    AlterAction::AddColumn { column } => table.columns.push(column),
+                    AlterAction::AlterColumn { name, action } => {
+                        let Some(column) = table.columns.iter_mut().find(|c| c.name == name) else {
+                            bail!("ALTER COLUMN on unknown column {}.{}", table.name, name);
+                        };
+                        match action {
+                            AlterColumnAction::SetType { typ, .. } => column.typ = typ,
+                            AlterColumnAction::SetNullable(nullable) => column.nullable = nullable,
+                        }
+                    }
+                    // Constraints are read by `table_constraint`.
+                    AlterAction::AddConstraint { .. } => unreachable!(),
+                }
+            }
+            if !self.eat_punct(",") {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToSql;
+
+    #[test]
+    fn test_select() {
+        let sql = r#"SELECT u.id, count(*) AS n, max(p.created_at) latest
+FROM users u
+LEFT JOIN posts AS p ON p.user_id = u.id AND p.deleted_at IS NULL
+WHERE u.name ILIKE 'k%' OR u.id IN (1, 2)
+GROUP BY u.id
+HAVING count(*) > 1
+ORDER BY n DESC NULLS LAST
+LIMIT 10 OFFSET 20"#;
+        let select = Select::parse(sql, Dialect::Postgres).unwrap();
+        assert_eq!(select.limit, Some(10));
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT "u"."id", count(*) AS "n", max("p"."created_at") AS "latest" FROM "users" AS "u" LEFT JOIN "posts" AS "p" ON "p"."user_id" = "u"."id" AND "p"."deleted_at" IS NULL WHERE ("u"."name" ILIKE 'k%' OR "u"."id" IN (1, 2)) GROUP BY "u"."id" HAVING count(*) > 1 ORDER BY n DESC NULLS LAST LIMIT 10 OFFSET 20"#
+        );
+
+        // Rewrite, and render for another dialect.
+        let select = select
+            .where_(Where::Expr(Expr::new_eq(
+                Expr::table_column("u", "tenant_id"),
+                Expr::literal(7),
+            )))
+            .limit(5);
+        assert_eq!(
+            select.to_sql(Dialect::Mysql),
+            "SELECT `u`.`id`, count(*) AS `n`, max(`p`.`created_at`) AS `latest` FROM `users` AS `u` LEFT JOIN `posts` AS `p` ON `p`.`user_id` = `u`.`id` AND `p`.`deleted_at` IS NULL WHERE (LOWER(`u`.`name`) LIKE LOWER('k%') OR `u`.`id` IN (1, 2)) AND `u`.`tenant_id` = 7 GROUP BY `u`.`id` HAVING count(*) > 1 ORDER BY n DESC NULLS LAST LIMIT 5 OFFSET 20"
+        );
+    }
+
+    #[test]
+    fn test_raw_fallback() {
+        let sql = "SELECT data->>'name' AS name, ARRAY[1, 2] FROM Events e WHERE e.payload @> '{}' AND e.id = $1";
+        let select = Select::parse(sql, Dialect::Postgres).unwrap();
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT data->>'name' AS "name", ARRAY[1, 2] FROM "events" AS "e" WHERE e.payload @> '{}' AND "e"."id" = $1"#
+        );
+        let select = Select::parse(
+            "SELECT * FROM events WHERE payload @> '{}' OR id = 1",
+            Dialect::Postgres,
+        )
+        .unwrap()
+        .where_(Where::Expr(Expr::new_eq(
+            Expr::column("tenant_id"),
+            Expr::literal(7),
+        )));
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT * FROM "events" WHERE (payload @> '{}' OR id = 1) AND "tenant_id" = 7"#
+        );
+        assert!(Select::parse("SELECT FROM users", Dialect::Postgres).is_err());
+        let err = Select::parse("SELECT id\nFROM users\nWHERE", Dialect::Postgres).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Expected an expression, found end of input"
+        );
+        let err = Select::parse("SELECT id FROM users\nSELECT", Dialect::Postgres).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Expected end of statement at line 2, found `SELECT`"
+        );
+        let err = Select::parse("SELECT id FROM users\nWHERE a = 'unterminated", Dialect::Postgres)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Unterminated quote at line 2");
+    }
+
+    #[test]
+    fn test_literals() {
+        let sql = "SELECT 1.5, 12345678901234567.89, 1e400, -2.5e-3, CAST(y AS text), z::int, w::bigint";
+        let select = Select::parse(sql, Dialect::Postgres).unwrap();
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT 1.5, 12345678901234567.89, 1e400, -2.5e-3, "y"::text, "z"::int, "w"::bigint"#
+        );
+        assert_eq!(
+            select.to_sql(Dialect::Sqlite),
+            r#"SELECT 1.5, 12345678901234567.89, 1e400, -2.5e-3, CAST("y" AS text), CAST("z" AS int), CAST("w" AS INTEGER)"#
+        );
+    }
+
+    #[test]
+    fn test_column_name_parts() {
+        let err = Parser::new("db.s.t.col", Dialect::Postgres)
+            .statement(Parser::expr)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Too many parts in column name db.s.t.col");
+    }
+
+    #[test]
+    fn test_concat_precedence() {
+        let sql = "SELECT a + b || c, a || b * c";
+        let select = Select::parse(sql, Dialect::Sqlite).unwrap();
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT "a" + ("b" || "c"), ("a" || "b") * "c""#
+        );
+        let select = Select::parse(sql, Dialect::Postgres).unwrap();
+        assert_eq!(
+            select.to_sql(Dialect::Sqlite),
+            r#"SELECT ("a" + "b") || "c", "a" || ("b" * "c")"#
+        );
+    }
+
+    #[test]
+    fn test_compound_and_ctes() {
+        let sql = "WITH RECURSIVE t(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 5) SELECT n FROM t FOR UPDATE SKIP LOCKED";
+        let select = Select::parse(sql, Dialect::Postgres).unwrap();
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"WITH RECURSIVE "t" ("n") AS (SELECT 1 UNION ALL SELECT "n" + 1 FROM "t" WHERE "n" < 5) SELECT "n" FROM "t" FOR UPDATE SKIP LOCKED"#
+        );
+
+        let sql = "SELECT id FROM a UNION (SELECT id FROM b LIMIT 5) ORDER BY id LIMIT 10";
+        let compound = Compound::parse(sql, Dialect::Sqlite).unwrap();
+        assert_eq!(compound.limit, Some(10));
+        assert_eq!(
+            compound.to_sql(Dialect::Sqlite),
+            r#"SELECT "id" FROM "a" UNION SELECT * FROM (SELECT "id" FROM "b" LIMIT 5) ORDER BY id LIMIT 10"#
+        );
+        assert!(Select::parse(sql, Dialect::Sqlite).is_err());
+    }
+
+    #[test]
+    fn test_insert() {
+        let sql = "INSERT INTO users (id, name) VALUES (1, 'it''s'), (2, DEFAULT) ON CONFLICT (id) DO UPDATE SET name = excluded.name WHERE users.name IS NULL RETURNING id";
+        let insert = Insert::parse(sql, Dialect::Postgres).unwrap();
+        assert_eq!(
+            insert.to_sql(Dialect::Postgres),
+            r#"INSERT INTO "users" ("id", "name") VALUES (1, 'it''s'), (2, DEFAULT) ON CONFLICT ("id") DO UPDATE SET "name" = "excluded"."name" WHERE "users"."name" IS NULL RETURNING "id""#
+        );
+
+        let sql = "INSERT INTO users (id, name) VALUES (1, 'a') AS new ON DUPLICATE KEY UPDATE name = new.name";
+        let insert = Insert::parse(sql, Dialect::Mysql).unwrap();
+        assert_eq!(insert.row_alias.as_deref(), Some("new"));
+        assert_eq!(
+            insert.to_sql(Dialect::Mysql),
+            "INSERT INTO `users` (`id`, `name`) VALUES (1, 'a') AS `new` ON DUPLICATE KEY UPDATE `name` = `new`.`name`"
+        );
+
+        let insert =
+            Insert::parse("INSERT OR IGNORE INTO t SELECT * FROM s", Dialect::Sqlite).unwrap();
+        assert_eq!(insert.on_conflict, OnConflict::Ignore);
+        assert!(matches!(insert.values, Values::Select(_)));
+    }
+
+    #[test]
+    fn test_update_delete() {
+        let sql = "UPDATE orders o SET status = 'shipped', total = total * 1.5 FROM shipments s WHERE s.order_id = o.id RETURNING o.id";
+        let update = Update::parse(sql, Dialect::Postgres).unwrap();
+        assert_eq!(
+            update.to_sql(Dialect::Postgres),
+            r#"UPDATE "orders" AS "o" SET "status" = 'shipped', "total" = "total" * 1.5 FROM "shipments" AS "s" WHERE "s"."order_id" = "o"."id" RETURNING "o"."id""#
+        );
+
+        let sql = "DELETE FROM sessions WHERE expires_at < now() ORDER BY id LIMIT 100";
+        let delete = Delete::parse(sql, Dialect::Sqlite).unwrap();
+        assert_eq!(
+            delete.to_sql(Dialect::Mysql),
+            "DELETE FROM `sessions` WHERE `expires_at` < now() ORDER BY id LIMIT 100"
+        );
+    }
+
+    #[test]
+    fn test_ddl() {
+        let sql = r#"
+CREATE TABLE IF NOT EXISTS public.posts (
+    id bigint GENERATED BY DEFAULT AS IDENTITY,
+    author_id integer NOT NULL,
+    title character varying(255) NOT NULL DEFAULT '',
+    score numeric(10, 2),
+    tags text[],
+    created_at timestamp with time zone NOT NULL DEFAULT now(),
+    PRIMARY KEY (id),
+    CONSTRAINT fk_author FOREIGN KEY (author_id) REFERENCES users (id)
+);"#;
+        let table = Table::parse(sql, Dialect::Postgres).unwrap();
+        assert_eq!(table.schema.as_deref(), Some("public"));
+        let types: Vec<_> = table.columns.iter().map(|c| c.typ.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                Type::I64,
+                Type::I32,
+                Type::Text,
+                Type::Numeric(10, 2),
+                Type::Array(Box::new(Type::Text)),
+                Type::DateTime
+            ]
+        );
+        assert!(table.columns[0].primary_key);
+        assert_eq!(
+            table.columns[1].constraint,
+            Some(Constraint::foreign_key(
+                "users".to_string(),
+                vec!["id".to_string()]
+            ))
+        );
+        assert_eq!(table.columns[5].default, Some(Function::now().into()));
+
+        let err =
+            Table::parse("CREATE TABLE t (\n  id int UNIQUE\n)", Dialect::Postgres).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unsupported column constraint `UNIQUE` at line 2"
+        );
+
+        let index = Index::parse(
+            "CREATE UNIQUE INDEX idx_users_email ON users USING hash (email)",
+            Dialect::Postgres,
+        )
+        .unwrap();
+        assert_eq!(
+            index.to_sql(Dialect::Postgres),
+            r#"CREATE UNIQUE INDEX "idx_users_email" ON "users" USING HASH ("email")"#
+        );
+
+        let alter = AlterTable::parse(
+            "ALTER TABLE users ADD COLUMN age integer, ALTER COLUMN name SET NOT NULL",
+            Dialect::Postgres,
+        )
+        .unwrap();
+        assert_eq!(
+            alter.to_sql(Dialect::Postgres),
+            r#"ALTER TABLE "users" ADD COLUMN "age" integer, ALTER COLUMN "name" SET NOT NULL"#
+        );
+    }
+
+    #[test]
+    fn test_schema_from_ddl() {
+        let sql = r#"
+-- users
+CREATE TABLE public.users (
+    id integer NOT NULL,
+    email text NOT NULL
+);
+
+CREATE TABLE public.posts (
+    id integer NOT NULL,
+    user_id integer
+);
+
+ALTER TABLE ONLY public.users
+    ADD CONSTRAINT users_pkey PRIMARY KEY (id);
+ALTER TABLE ONLY public.posts
+    ADD CONSTRAINT posts_user_id_fkey FOREIGN KEY (user_id) REFERENCES public.users(id),
+    ADD COLUMN body text;
+CREATE UNIQUE INDEX users_email_idx ON public.users USING btree (email);
+"#;
+        let schema = Schema::try_from_ddl(sql, Dialect::Postgres).unwrap();
+        assert_eq!(schema.tables.len(), 2);
+        assert!(schema.tables[0].columns[0].primary_key);
+        let posts = &schema.tables[1];
+        assert_eq!(
+            posts.columns[1].constraint,
+            Some(Constraint::ForeignKey(ForeignKey {
+                schema: Some("public".to_string()),
+                table: "users".to_string(),
+                columns: vec!["id".to_string()],
+            }))
+        );
+        assert_eq!(posts.columns[2].name, "body");
+        assert_eq!(schema.indexes[0].columns, vec!["email".to_string()]);
+
+        let migration = Schema::default()
+            .migrate_to(schema, &Default::default())
+            .unwrap();
+        assert_eq!(migration.statements.len(), 3);
+
+        let sql = "CREATE EXTENSION pgcrypto;\nCREATE TABLE t (\n  id int UNIQUE\n);\nCREATE TABLE u (id int);";
+        let err = Schema::try_from_ddl(sql, Dialect::Postgres).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to read schema from DDL:\nUnsupported statement `CREATE EXTENSION` at line 1\nUnsupported column constraint `UNIQUE` at line 3"
+        );
+
+        let sql = "CREATE TABLE m (a int, b int, PRIMARY KEY (a, b));\nALTER TABLE m ADD CONSTRAINT m_fkey FOREIGN KEY (a, b) REFERENCES n(a, b);";
+        let err = Schema::try_from_ddl(sql, Dialect::Postgres).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to read schema from DDL:\nUnsupported multi-column constraint at line 2"
+        );
+        let schema = Schema::try_from_ddl(&sql[..sql.find('\n').unwrap()], Dialect::Postgres).unwrap();
+        assert_eq!(
+            schema.tables[0].to_sql(Dialect::Postgres),
+            "CREATE TABLE IF NOT EXISTS \"m\" (\n\"a\" integer NOT NULL,\n\"b\" integer NOT NULL,\nPRIMARY KEY (\"a\", \"b\")\n)"
+        );
+    }
+}
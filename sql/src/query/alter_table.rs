@@ -3,6 +3,7 @@ use crate::util::SqlExtension;
 use crate::{Column, Dialect, ToSql, Type};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AlterColumnAction {
     SetType { typ: Type, using: Option<String> },
     SetNullable(bool),
@@ -10,6 +11,8 @@ pub enum AlterColumnAction {
 
 /// Alter table action
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::large_enum_variant)]
 pub enum AlterAction {
     AddColumn {
         column: Column,
@@ -51,6 +54,7 @@ impl AlterAction {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AlterTable {
     pub schema: Option<String>,
     pub name: String,
@@ -70,7 +74,7 @@ impl ToSql for AlterTable {
             )
         );
         buf.push_str("ALTER TABLE ");
-        buf.push_table_name(&self.schema, &self.name);
+        buf.push_table_name(&self.schema, &self.name, dialect);
         buf.push_sql_sequence(&self.actions, ",", dialect);
     }
 }
@@ -86,16 +90,16 @@ impl ToSql for AlterAction {
             AlterColumn { name, action } => {
                 use AlterColumnAction::*;
                 buf.push_str(" ALTER COLUMN ");
-                buf.push_quoted(name);
+                buf.push_quoted(name, dialect);
                 match action {
                     SetType { typ, using } => {
                         buf.push_str(" TYPE ");
                         buf.push_sql(typ, dialect);
                         buf.push_str(" USING ");
                         if let Some(using) = using {
-                            buf.push_str(&using)
+                            buf.push_str(using)
                         } else {
-                            buf.push_quoted(name);
+                            buf.push_quoted(name, dialect);
                             buf.push_str("::");
                             buf.push_sql(typ, dialect);
                         }
@@ -115,9 +119,9 @@ impl ToSql for AlterAction {
                 constraint,
             } => {
                 buf.push_str(" ADD CONSTRAINT ");
-                buf.push_quoted(name);
+                buf.push_quoted(name, dialect);
                 buf.push_str(" FOREIGN KEY (");
-                buf.push_quoted(column);
+                buf.push_quoted(column, dialect);
                 buf.push_str(") ");
                 buf.push_sql(constraint, dialect);
             }
@@ -0,0 +1,245 @@
+use crate::query::OrderBy;
+use crate::util::SqlExtension;
+use crate::{Dialect, Select, ToSql};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+/// A set operator and its quantifier, e.g. `UNION ALL`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetOperation {
+    pub operator: SetOperator,
+    /// Keep duplicate rows. If false, duplicates are removed, which is the SQL default
+    /// (`DISTINCT`). Sqlite only supports `ALL` for `UNION`.
+    pub all: bool,
+}
+
+impl ToSql for SetOperation {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        match self.operator {
+            SetOperator::Union => buf.push_str("UNION"),
+            SetOperator::Intersect => buf.push_str("INTERSECT"),
+            SetOperator::Except => buf.push_str("EXCEPT"),
+        }
+        if self.all {
+            if dialect == Dialect::Sqlite && self.operator != SetOperator::Union {
+                panic!("Sqlite does not support INTERSECT ALL or EXCEPT ALL");
+            }
+            buf.push_str(" ALL");
+        }
+    }
+}
+
+/// An operand of a compound query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompoundQuery {
+    Select(Box<Select>),
+    /// A nested compound, rendered in parentheses.
+    Compound(Box<Compound>),
+}
+
+impl From<Select> for CompoundQuery {
+    fn from(select: Select) -> Self {
+        CompoundQuery::Select(Box::new(select))
+    }
+}
+
+impl From<Compound> for CompoundQuery {
+    fn from(compound: Compound) -> Self {
+        CompoundQuery::Compound(Box::new(compound))
+    }
+}
+
+impl CompoundQuery {
+    /// Whether the operand has to be grouped to keep its own clauses, e.g. an ORDER BY that
+    /// would otherwise apply to the whole compound.
+    fn needs_grouping(&self) -> bool {
+        match self {
+            CompoundQuery::Select(s) => {
+                !s.ctes.is_empty() || !s.order.is_empty() || s.limit.is_some() || s.offset.is_some()
+            }
+            CompoundQuery::Compound(_) => true,
+        }
+    }
+}
+
+impl ToSql for CompoundQuery {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        let grouped = self.needs_grouping();
+        if grouped {
+            // Sqlite does not allow parentheses around compound operands.
+            if dialect == Dialect::Sqlite {
+                buf.push_str("SELECT * FROM ");
+            }
+            buf.push('(');
+        }
+        match self {
+            CompoundQuery::Select(s) => s.write_sql(buf, dialect),
+            CompoundQuery::Compound(c) => c.write_sql(buf, dialect),
+        }
+        if grouped {
+            buf.push(')');
+        }
+    }
+}
+
+/// A compound query, combining queries with `UNION`, `INTERSECT` and `EXCEPT`.
+///
+/// Operators are applied left to right. Nest a `Compound` to group operands differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compound {
+    pub first: CompoundQuery,
+    pub rest: Vec<(SetOperation, CompoundQuery)>,
+    /// Applies to the whole compound.
+    pub order: Vec<OrderBy>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+impl Compound {
+    pub fn new(first: impl Into<CompoundQuery>) -> Self {
+        Self {
+            first: first.into(),
+            rest: vec![],
+            order: vec![],
+            limit: None,
+            offset: None,
+        }
+    }
+
+    pub fn push(
+        mut self,
+        operator: SetOperator,
+        all: bool,
+        query: impl Into<CompoundQuery>,
+    ) -> Self {
+        self.rest
+            .push((SetOperation { operator, all }, query.into()));
+        self
+    }
+
+    pub fn union(self, query: impl Into<CompoundQuery>) -> Self {
+        self.push(SetOperator::Union, false, query)
+    }
+
+    pub fn union_all(self, query: impl Into<CompoundQuery>) -> Self {
+        self.push(SetOperator::Union, true, query)
+    }
+
+    pub fn intersect(self, query: impl Into<CompoundQuery>) -> Self {
+        self.push(SetOperator::Intersect, false, query)
+    }
+
+    pub fn intersect_all(self, query: impl Into<CompoundQuery>) -> Self {
+        self.push(SetOperator::Intersect, true, query)
+    }
+
+    pub fn except(self, query: impl Into<CompoundQuery>) -> Self {
+        self.push(SetOperator::Except, false, query)
+    }
+
+    pub fn except_all(self, query: impl Into<CompoundQuery>) -> Self {
+        self.push(SetOperator::Except, true, query)
+    }
+
+    pub fn order_by(mut self, order: OrderBy) -> Self {
+        self.order.push(order);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl ToSql for Compound {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        buf.push_sql(&self.first, dialect);
+        for (operation, query) in &self.rest {
+            buf.push(' ');
+            buf.push_sql(operation, dialect);
+            buf.push(' ');
+            buf.push_sql(query, dialect);
+        }
+        if !self.order.is_empty() {
+            buf.push_str(" ORDER BY ");
+            buf.push_sql_sequence(&self.order, ", ", dialect);
+        }
+        if let Some(limit) = self.limit {
+            buf.push_str(" LIMIT ");
+            buf.push_str(&limit.to_string());
+        }
+        if let Some(offset) = self.offset {
+            buf.push_str(" OFFSET ");
+            buf.push_str(&offset.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{Insert, Join, JoinTable, Values};
+
+    fn table(name: &str) -> Select {
+        Select::default().select_raw("id").from(name)
+    }
+
+    #[test]
+    fn test_nested() {
+        let q = Compound::new(table("a"))
+            .union_all(Compound::new(table("b")).except(table("c")))
+            .order_by(OrderBy::new("id"))
+            .limit(10);
+        assert_eq!(
+            q.to_sql(Dialect::Postgres),
+            r#"SELECT id FROM "a" UNION ALL (SELECT id FROM "b" EXCEPT SELECT id FROM "c") ORDER BY id LIMIT 10"#
+        );
+        assert_eq!(
+            q.to_sql(Dialect::Sqlite),
+            r#"SELECT id FROM "a" UNION ALL SELECT * FROM (SELECT id FROM "b" EXCEPT SELECT id FROM "c") ORDER BY id LIMIT 10"#
+        );
+    }
+
+    #[test]
+    fn test_as_source() {
+        let q = Compound::new(table("a")).intersect(table("b").limit(5));
+        let select = Select::default()
+            .with("ids", q.clone())
+            .select_raw("*")
+            .from("ids");
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"WITH "ids" AS (SELECT id FROM "a" INTERSECT (SELECT id FROM "b" LIMIT 5)) SELECT * FROM "ids""#
+        );
+
+        let join = Join {
+            table: JoinTable::Compound(q.clone()),
+            ..Join::new("").alias("ids").on_raw("ids.id = t.id")
+        };
+        assert_eq!(
+            join.to_sql(Dialect::Mysql),
+            "JOIN (SELECT id FROM `a` INTERSECT (SELECT id FROM `b` LIMIT 5)) AS `ids` ON ids.id = t.id"
+        );
+
+        let insert = Insert::new("ids").column("id").values(Values::Compound(q));
+        assert_eq!(
+            insert.to_sql(Dialect::Postgres),
+            r#"INSERT INTO "ids" ("id") SELECT id FROM "a" INTERSECT (SELECT id FROM "b" LIMIT 5)"#
+        );
+    }
+}
@@ -2,6 +2,7 @@ use crate::{Dialect, ToSql};
 use crate::util::SqlExtension;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreateSchema {
     pub name: String,
     pub if_not_exists: bool,
@@ -22,11 +23,11 @@ impl CreateSchema {
 }
 
 impl ToSql for CreateSchema {
-    fn write_sql(&self, buf: &mut String, _: Dialect) {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         buf.push_str("CREATE SCHEMA ");
         if self.if_not_exists {
             buf.push_str(" IF NOT EXISTS ");
         }
-        buf.push_quoted(&self.name);
+        buf.push_quoted(&self.name, dialect);
     }
 }
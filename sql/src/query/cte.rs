@@ -1,9 +1,15 @@
-use crate::{Dialect, Insert, Select, ToSql};
+use crate::util::SqlExtension;
+use crate::{Compound, Delete, Dialect, Insert, Select, ToSql, Update};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::large_enum_variant)]
 pub enum CteQuery {
     Select(Select),
+    Compound(Compound),
     Insert(Insert),
+    Update(Update),
+    Delete(Delete),
     Raw(String),
 }
 
@@ -11,51 +17,260 @@ impl ToSql for CteQuery {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         match self {
             CteQuery::Select(s) => s.write_sql(buf, dialect),
+            CteQuery::Compound(c) => c.write_sql(buf, dialect),
             CteQuery::Insert(i) => i.write_sql(buf, dialect),
+            CteQuery::Update(u) => u.write_sql(buf, dialect),
+            CteQuery::Delete(d) => d.write_sql(buf, dialect),
             CteQuery::Raw(s) => buf.push_str(s),
         }
     }
 }
 
-impl Into<CteQuery> for Select {
-    fn into(self) -> CteQuery {
-        CteQuery::Select(self)
+impl From<Select> for CteQuery {
+    fn from(select: Select) -> Self {
+        CteQuery::Select(select)
     }
 }
 
-impl Into<CteQuery> for Insert {
-    fn into(self) -> CteQuery {
-        CteQuery::Insert(self)
+impl From<Compound> for CteQuery {
+    fn from(compound: Compound) -> Self {
+        CteQuery::Compound(compound)
     }
 }
 
-impl Into<CteQuery> for String {
-    fn into(self) -> CteQuery {
-        CteQuery::Raw(self)
+impl From<Insert> for CteQuery {
+    fn from(insert: Insert) -> Self {
+        CteQuery::Insert(insert)
     }
 }
 
+impl From<Update> for CteQuery {
+    fn from(update: Update) -> Self {
+        CteQuery::Update(update)
+    }
+}
+
+impl From<Delete> for CteQuery {
+    fn from(delete: Delete) -> Self {
+        CteQuery::Delete(delete)
+    }
+}
+
+impl From<String> for CteQuery {
+    fn from(raw: String) -> Self {
+        CteQuery::Raw(raw)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Materialized {
+    Materialized,
+    NotMaterialized,
+}
+
+/// Postgres `SEARCH` clause of a recursive CTE, which adds an ordering column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Search {
+    pub depth_first: bool,
+    pub by: Vec<String>,
+    pub set: String,
+}
+
+/// Postgres `CYCLE` clause of a recursive CTE, which stops recursion when a row repeats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cycle {
+    pub columns: Vec<String>,
+    /// Column marking whether a cycle was detected.
+    pub set: String,
+    /// Column holding the path of visited rows.
+    pub using: String,
+}
+
 /// Common table expression
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cte {
     pub name: String,
+    pub columns: Vec<String>,
     pub query: CteQuery,
+    /// The query refers to the CTE itself. Any recursive CTE makes the clause `WITH RECURSIVE`.
+    pub recursive: bool,
+    /// Not supported by Mysql, where the hint is omitted.
+    pub materialized: Option<Materialized>,
+    /// Only valid for Postgres
+    pub search: Option<Search>,
+    /// Only valid for Postgres
+    pub cycle: Option<Cycle>,
 }
 
 impl Cte {
     pub fn new(name: impl Into<String>, query: impl Into<CteQuery>) -> Self {
         Self {
             name: name.into(),
+            columns: vec![],
             query: query.into(),
+            recursive: false,
+            materialized: None,
+            search: None,
+            cycle: None,
+        }
+    }
+
+    pub fn recursive(name: impl Into<String>, query: impl Into<CteQuery>) -> Self {
+        Self {
+            recursive: true,
+            ..Self::new(name, query)
         }
     }
+
+    pub fn columns(mut self, columns: &[&str]) -> Self {
+        self.columns = columns.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub fn materialized(mut self) -> Self {
+        self.materialized = Some(Materialized::Materialized);
+        self
+    }
+
+    pub fn not_materialized(mut self) -> Self {
+        self.materialized = Some(Materialized::NotMaterialized);
+        self
+    }
+
+    pub fn search_depth_first(mut self, by: &[&str], set: &str) -> Self {
+        self.search = Some(Search {
+            depth_first: true,
+            by: by.iter().map(|c| c.to_string()).collect(),
+            set: set.to_string(),
+        });
+        self
+    }
+
+    pub fn search_breadth_first(mut self, by: &[&str], set: &str) -> Self {
+        self.search = Some(Search {
+            depth_first: false,
+            by: by.iter().map(|c| c.to_string()).collect(),
+            set: set.to_string(),
+        });
+        self
+    }
+
+    pub fn cycle(mut self, columns: &[&str], set: &str, using: &str) -> Self {
+        self.cycle = Some(Cycle {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            set: set.to_string(),
+            using: using.to_string(),
+        });
+        self
+    }
+}
+
+/// Write the `WITH` clause, followed by a space, if there are any CTEs.
+pub(crate) fn write_ctes(buf: &mut String, ctes: &[Cte], dialect: Dialect) {
+    if ctes.is_empty() {
+        return;
+    }
+    buf.push_str("WITH ");
+    if ctes.iter().any(|c| c.recursive) {
+        buf.push_str("RECURSIVE ");
+    }
+    buf.push_sql_sequence(ctes, ", ", dialect);
+    buf.push(' ');
 }
 
 impl ToSql for Cte {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
-        buf.push_str(&self.name);
-        buf.push_str(" AS (");
+        buf.push_quoted(&self.name, dialect);
+        if !self.columns.is_empty() {
+            buf.push_str(" (");
+            buf.push_quoted_sequence(&self.columns, ", ", dialect);
+            buf.push(')');
+        }
+        buf.push_str(" AS ");
+        match (self.materialized, dialect) {
+            (_, Dialect::Mysql) | (None, _) => {}
+            (Some(Materialized::Materialized), _) => buf.push_str("MATERIALIZED "),
+            (Some(Materialized::NotMaterialized), _) => buf.push_str("NOT MATERIALIZED "),
+        }
+        buf.push('(');
         buf.push_str(&self.query.to_sql(dialect));
         buf.push(')');
+        if (self.search.is_some() || self.cycle.is_some()) && dialect != Dialect::Postgres {
+            panic!("SEARCH and CYCLE clauses are only supported by Postgres");
+        }
+        if let Some(search) = &self.search {
+            buf.push_str(if search.depth_first {
+                " SEARCH DEPTH FIRST BY "
+            } else {
+                " SEARCH BREADTH FIRST BY "
+            });
+            buf.push_quoted_sequence(&search.by, ", ", dialect);
+            buf.push_str(" SET ");
+            buf.push_quoted(&search.set, dialect);
+        }
+        if let Some(cycle) = &self.cycle {
+            buf.push_str(" CYCLE ");
+            buf.push_quoted_sequence(&cycle.columns, ", ", dialect);
+            buf.push_str(" SET ");
+            buf.push_quoted(&cycle.set, dialect);
+            buf.push_str(" USING ");
+            buf.push_quoted(&cycle.using, dialect);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{Compound, From};
+
+    #[test]
+    fn test_recursive() {
+        let tree = Compound::new(
+            Select::default()
+                .select_raw("id")
+                .select_raw("parent_id")
+                .from("categories")
+                .where_raw("parent_id IS NULL"),
+        )
+        .union_all(
+            Select::default()
+                .select_raw("c.id")
+                .select_raw("c.parent_id")
+                .from(From::table("categories").alias("c"))
+                .where_raw("c.parent_id = tree.id"),
+        );
+        let cte = Cte::recursive("tree", tree)
+            .columns(&["id", "parent_id"])
+            .search_depth_first(&["id"], "ordercol")
+            .cycle(&["id"], "is_cycle", "path");
+        let select = Select::default()
+            .with("roots", Select::default().select_raw("1"))
+            .with_cte(cte)
+            .select_raw("*")
+            .from("tree");
+        let expected = [
+            r#"WITH RECURSIVE "roots" AS (SELECT 1), "#,
+            r#""tree" ("id", "parent_id") AS (SELECT id, parent_id FROM "categories" WHERE parent_id IS NULL "#,
+            r#"UNION ALL SELECT c.id, c.parent_id FROM "categories" AS "c" WHERE c.parent_id = tree.id) "#,
+            r#"SEARCH DEPTH FIRST BY "id" SET "ordercol" CYCLE "id" SET "is_cycle" USING "path" "#,
+            r#"SELECT * FROM "tree""#,
+        ]
+        .concat();
+        assert_eq!(select.to_sql(Dialect::Postgres), expected);
+    }
+
+    #[test]
+    fn test_materialized() {
+        let cte = Cte::new("t", Select::default().select_raw("1")).not_materialized();
+        assert_eq!(
+            cte.to_sql(Dialect::Postgres),
+            r#""t" AS NOT MATERIALIZED (SELECT 1)"#
+        );
+        assert_eq!(cte.to_sql(Dialect::Mysql), "`t` AS (SELECT 1)");
     }
 }
@@ -0,0 +1,293 @@
+use crate::query::{Cte, CteQuery, From, Join, OrderBy, Where};
+use crate::query::cte::write_ctes;
+use crate::util::{SqlExtension, push_returning};
+use crate::{Dialect, ToSql};
+
+/// A DELETE query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delete {
+    pub ctes: Vec<Cte>,
+    pub schema: Option<String>,
+    pub table: String,
+    pub alias: Option<String>,
+    /// Additional tables to delete rows from. Only valid for Mysql (multi-table delete)
+    pub targets: Vec<String>,
+    /// Tables joined into the delete. Rendered as `USING` on Postgres, and as the
+    /// `FROM` table list on Mysql.
+    pub using: Vec<From>,
+    pub join: Vec<Join>,
+    pub where_: Where,
+    /// Only valid for Mysql and Sqlite
+    pub order: Vec<OrderBy>,
+    /// Only valid for Mysql and Sqlite
+    pub limit: Option<usize>,
+    pub returning: Vec<String>,
+}
+
+impl Delete {
+    pub fn new(table: &str) -> Self {
+        Self {
+            ctes: vec![],
+            schema: None,
+            table: table.to_string(),
+            alias: None,
+            targets: vec![],
+            using: vec![],
+            join: vec![],
+            where_: Where::And(vec![]),
+            order: vec![],
+            limit: None,
+            returning: vec![],
+        }
+    }
+
+    pub fn with(mut self, name: &str, query: impl Into<CteQuery>) -> Self {
+        self.ctes.push(Cte::new(name, query));
+        self
+    }
+
+    pub fn with_cte(mut self, cte: Cte) -> Self {
+        self.ctes.push(cte);
+        self
+    }
+
+    pub fn schema(mut self, schema: &str) -> Self {
+        self.schema = Some(schema.to_string());
+        self
+    }
+
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.alias = Some(alias.to_string());
+        self
+    }
+
+    pub fn target(mut self, table: &str) -> Self {
+        self.targets.push(table.to_string());
+        self
+    }
+
+    pub fn using(mut self, table: impl Into<From>) -> Self {
+        self.using.push(table.into());
+        self
+    }
+
+    pub fn join(mut self, join: Join) -> Self {
+        self.join.push(join);
+        self
+    }
+
+    /// Assumes `AND`. Access the `.where_` field directly for more advanced operations.
+    pub fn where_(mut self, where_: Where) -> Self {
+        match self.where_ {
+            Where::And(ref mut v) => v.push(where_),
+            _ => self.where_ = Where::And(vec![self.where_, where_]),
+        }
+        self
+    }
+
+    pub fn where_raw(self, where_: impl Into<String>) -> Self {
+        self.where_(Where::raw(where_))
+    }
+
+    pub fn order_by(mut self, order: OrderBy) -> Self {
+        self.order.push(order);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn returning(mut self, returning: &[&str]) -> Self {
+        self.returning = returning.iter().map(|r| r.to_string()).collect();
+        self
+    }
+
+    fn is_multi_table(&self) -> bool {
+        !self.targets.is_empty() || !self.using.is_empty() || !self.join.is_empty()
+    }
+
+    fn write_target(&self, buf: &mut String, dialect: Dialect) {
+        buf.push_table_name(&self.schema, &self.table, dialect);
+        if let Some(alias) = &self.alias {
+            buf.push_str(" AS ");
+            buf.push_quoted(alias, dialect);
+        }
+    }
+}
+
+impl ToSql for Delete {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        use Dialect::*;
+        write_ctes(buf, &self.ctes, dialect);
+        match dialect {
+            Postgres => {
+                if !self.targets.is_empty() {
+                    panic!("Postgres does not support multi-table DELETE");
+                }
+                if self.using.is_empty() && !self.join.is_empty() {
+                    panic!("Postgres does not support JOIN in DELETE without a USING table");
+                }
+                buf.push_str("DELETE FROM ");
+                self.write_target(buf, dialect);
+                if !self.using.is_empty() {
+                    buf.push_str(" USING ");
+                    buf.push_sql_sequence(&self.using, ", ", dialect);
+                }
+                for join in &self.join {
+                    buf.push(' ');
+                    buf.push_sql(join, dialect);
+                }
+            }
+            Mysql if self.is_multi_table() => {
+                buf.push_str("DELETE ");
+                match &self.alias {
+                    Some(alias) => buf.push_quoted(alias, dialect),
+                    None => buf.push_table_name(&self.schema, &self.table, dialect),
+                }
+                for target in &self.targets {
+                    buf.push_str(", ");
+                    buf.push_quoted(target, dialect);
+                }
+                buf.push_str(" FROM ");
+                self.write_target(buf, dialect);
+                for using in &self.using {
+                    buf.push_str(", ");
+                    buf.push_sql(using, dialect);
+                }
+                for join in &self.join {
+                    buf.push(' ');
+                    buf.push_sql(join, dialect);
+                }
+            }
+            Mysql => {
+                buf.push_str("DELETE FROM ");
+                self.write_target(buf, dialect);
+            }
+            Sqlite => {
+                if self.is_multi_table() {
+                    panic!("Sqlite does not support DELETE with USING or JOIN");
+                }
+                buf.push_str("DELETE FROM ");
+                self.write_target(buf, dialect);
+            }
+        }
+        if !self.where_.is_empty() {
+            buf.push_str(" WHERE ");
+            self.where_.write_sql(buf, dialect);
+        }
+        if !self.returning.is_empty() {
+            if dialect == Mysql {
+                panic!("Mysql does not support RETURNING in DELETE");
+            }
+            push_returning(buf, &self.returning, dialect);
+        }
+        if dialect == Postgres && (!self.order.is_empty() || self.limit.is_some()) {
+            panic!("Postgres does not support ORDER BY or LIMIT in DELETE");
+        }
+        if dialect == Mysql
+            && self.is_multi_table()
+            && (!self.order.is_empty() || self.limit.is_some())
+        {
+            panic!("Mysql does not support ORDER BY or LIMIT in multi-table DELETE");
+        }
+        if !self.order.is_empty() {
+            buf.push_str(" ORDER BY ");
+            buf.push_sql_sequence(&self.order, ", ", dialect);
+        }
+        if let Some(limit) = self.limit {
+            buf.push_str(" LIMIT ");
+            buf.push_str(&limit.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Select;
+
+    #[test]
+    fn test_basic() {
+        let delete = Delete::new("users")
+            .schema("public")
+            .where_raw("id = $1")
+            .returning(&["id"]);
+        assert_eq!(
+            delete.to_sql(Dialect::Postgres),
+            r#"DELETE FROM "public"."users" WHERE id = $1 RETURNING "id""#
+        );
+    }
+
+    #[test]
+    fn test_using() {
+        let delete = Delete::new("posts")
+            .alias("p")
+            .using("users")
+            .where_raw("p.user_id = users.id")
+            .where_raw("users.banned");
+        assert_eq!(
+            delete.to_sql(Dialect::Postgres),
+            r#"DELETE FROM "posts" AS "p" USING "users" WHERE p.user_id = users.id AND users.banned"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Postgres does not support JOIN in DELETE without a USING table")]
+    fn test_join_without_using() {
+        Delete::new("orders")
+            .join(Join::new("shipments").on_raw("shipments.order_id = orders.id"))
+            .to_sql(Dialect::Postgres);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mysql does not support RETURNING in DELETE")]
+    fn test_mysql_returning() {
+        Delete::new("users")
+            .where_raw("banned")
+            .returning(&["id"])
+            .to_sql(Dialect::Mysql);
+    }
+
+    #[test]
+    fn test_mysql_multi_table() {
+        let delete = Delete::new("posts")
+            .alias("p")
+            .target("c")
+            .join(Join::new("comments").alias("c").on_raw("c.post_id = p.id"))
+            .where_raw("p.id = ?");
+        assert_eq!(
+            delete.to_sql(Dialect::Mysql),
+            "DELETE `p`, `c` FROM `posts` AS `p` JOIN `comments` AS `c` ON c.post_id = p.id WHERE p.id = ?"
+        );
+    }
+
+    #[test]
+    fn test_order_limit() {
+        let delete = Delete::new("jobs")
+            .where_raw("done")
+            .order_by(OrderBy::new("created_at"))
+            .limit(100);
+        assert_eq!(
+            delete.to_sql(Dialect::Sqlite),
+            r#"DELETE FROM "jobs" WHERE done ORDER BY created_at LIMIT 100"#
+        );
+    }
+
+    #[test]
+    fn test_data_modifying_cte() {
+        let select = Select::default()
+            .with(
+                "deleted",
+                Delete::new("users").where_raw("banned").returning(&["id"]),
+            )
+            .select_raw("count(*)")
+            .from("deleted");
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"WITH "deleted" AS (DELETE FROM "users" WHERE banned RETURNING "id") SELECT count(*) FROM "deleted""#
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::{Dialect, Table, ToSql};
 
 /// Create table action
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DropTable {
     pub schema: Option<String>,
     pub name: String,
@@ -18,9 +19,9 @@ impl DropTable {
 }
 
 impl ToSql for DropTable {
-    fn write_sql(&self, buf: &mut String, _dialect: Dialect) {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         buf.push_str("DROP TABLE ");
-        buf.push_table_name(&self.schema, &self.name);
+        buf.push_table_name(&self.schema, &self.name, dialect);
     }
 }
 
@@ -1,24 +1,37 @@
-use crate::query::Expr;
-use crate::util::SqlExtension;
-use crate::{Dialect, Select, ToSql};
+use crate::query::{Compound, Expr, Function, Where};
+use crate::util::{SqlExtension, push_returning};
+use crate::{Dialect, Param, Select, ToSql};
+use anyhow::{Result, bail};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OnConflict {
+    /// Rendered as `INSERT IGNORE` on Mysql.
     Ignore,
+    #[default]
     Abort,
-    /// Only valid for Sqlite, because we
+    /// Only valid for Sqlite and Mysql (`REPLACE INTO`)
     Replace,
-    /// Only valid for Postgres
+    /// On Mysql, the conflict target is ignored, because `ON DUPLICATE KEY UPDATE` applies to any
+    /// unique key. With no `updates`, this is rendered like `DoUpdateAllRows` with nothing to
+    /// update.
     DoUpdate {
         conflict: Conflict,
         updates: Vec<(String, Expr)>,
+        /// Only update rows matching this condition. Not supported by Mysql.
+        where_: Where,
     },
-    /// Only valid for Postgres
+    /// On Mysql, columns are updated from `VALUES(col)`, or from the row alias if
+    /// `Insert::row_alias` is set. If every inserted column is ignored or part of the conflict
+    /// target, there is nothing to update, and this is rendered as `DO NOTHING`, or
+    /// `INSERT IGNORE` on Mysql.
     DoUpdateAllRows {
         conflict: Conflict,
         alternate_values: HashMap<String, Expr>,
         ignore_columns: Vec<String>,
+        /// Only update rows matching this condition. Not supported by Mysql.
+        where_: Where,
     },
 }
 
@@ -28,6 +41,7 @@ impl OnConflict {
             conflict: Conflict::Columns(columns.iter().map(|c| c.to_string()).collect()),
             alternate_values: HashMap::new(),
             ignore_columns: Vec::new(),
+            where_: Where::And(vec![]),
         }
     }
 
@@ -36,6 +50,7 @@ impl OnConflict {
             conflict: Conflict::Columns(vec![pkey.to_string()]),
             alternate_values: HashMap::new(),
             ignore_columns: Vec::new(),
+            where_: Where::And(vec![]),
         }
     }
 
@@ -50,9 +65,23 @@ impl OnConflict {
         }
         self
     }
+
+    pub fn where_(mut self, condition: Where) -> Self {
+        match &mut self {
+            OnConflict::DoUpdate { where_, .. } | OnConflict::DoUpdateAllRows { where_, .. } => {
+                match where_ {
+                    Where::And(v) => v.push(condition),
+                    _ => *where_ = Where::And(vec![where_.clone(), condition]),
+                }
+            }
+            _ => panic!("where_ is only valid for DoUpdate"),
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Conflict {
     Columns(Vec<String>),
     ConstraintName(String),
@@ -72,23 +101,17 @@ impl Conflict {
     }
 }
 
-impl Default for OnConflict {
-    fn default() -> Self {
-        OnConflict::Abort
-    }
-}
-
 impl ToSql for Conflict {
-    fn write_sql(&self, buf: &mut String, _dialect: Dialect) {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         match self {
             Conflict::Columns(c) => {
                 buf.push('(');
-                buf.push_quoted_sequence(c, ", ");
+                buf.push_quoted_sequence(c, ", ", dialect);
                 buf.push(')');
             }
             Conflict::ConstraintName(name) => {
                 buf.push_str("ON CONSTRAINT ");
-                buf.push_quoted(name);
+                buf.push_quoted(name, dialect);
             }
             Conflict::NoTarget => {}
         }
@@ -104,15 +127,8 @@ impl ToSql for Values {
                     if !first_value {
                         buf.push_str(", ");
                     }
-                    let mut first = true;
                     buf.push('(');
-                    for v in &value.0 {
-                        if !first {
-                            buf.push_str(", ");
-                        }
-                        buf.push_str(v);
-                        first = false;
-                    }
+                    buf.push_sql_sequence(&value.0, ", ", dialect);
                     buf.push(')');
                     first_value = false;
                 }
@@ -120,6 +136,12 @@ impl ToSql for Values {
             Values::Select(select) => {
                 buf.push_sql(select, dialect);
             }
+            Values::Compound(compound) => {
+                buf.push_sql(compound, dialect);
+            }
+            Values::DefaultValues if dialect == Dialect::Mysql => {
+                buf.push_str("VALUES ()");
+            }
             Values::DefaultValues => {
                 buf.push_str("DEFAULT VALUES");
             }
@@ -127,12 +149,13 @@ impl ToSql for Values {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Value(Vec<String>);
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Value(pub(crate) Vec<Expr>);
 
 impl Value {
     pub fn with(values: &[&str]) -> Self {
-        Self(values.into_iter().map(|v| v.to_string()).collect())
+        Self(values.iter().map(|&v| v.into()).collect())
     }
 
     pub fn new() -> Self {
@@ -140,16 +163,29 @@ impl Value {
     }
 
     pub fn column(mut self, value: &str) -> Self {
-        self.0.push(value.to_string());
+        self.0.push(value.into());
         self
     }
 
+    pub fn expr(mut self, value: impl Into<Expr>) -> Self {
+        self.0.push(value.into());
+        self
+    }
+
+    /// Add a bound parameter. Placeholders are numbered when the query is rendered.
+    pub fn param(mut self, value: impl Into<Param>) -> Self {
+        self.0.push(Expr::Param(value.into()));
+        self
+    }
+
+    /// Hand-numbered placeholders, starting at `$1`. Prefer `Value::param`, which numbers
+    /// placeholders across the whole query.
     pub fn placeholders(mut self, count: usize, dialect: Dialect) -> Self {
         use Dialect::*;
         for i in 1..(count + 1) {
             match dialect {
-                Postgres => self.0.push(format!("${}", i)),
-                Mysql | Sqlite => self.0.push("?".to_string()),
+                Postgres => self.0.push(Expr::Raw(format!("${}", i))),
+                Mysql | Sqlite => self.0.push(Expr::Raw("?".to_string())),
             }
         }
         self
@@ -158,20 +194,29 @@ impl Value {
 
 impl From<Vec<String>> for Value {
     fn from(values: Vec<String>) -> Self {
+        Self(values.into_iter().map(Expr::Raw).collect())
+    }
+}
+
+impl From<Vec<Expr>> for Value {
+    fn from(values: Vec<Expr>) -> Self {
         Self(values)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::large_enum_variant)]
 pub enum Values {
     Values(Vec<Value>),
     Select(Select),
+    Compound(Compound),
     DefaultValues,
 }
 
 impl From<&[&[&'static str]]> for Values {
     fn from(values: &[&[&'static str]]) -> Self {
-        Self::Values(values.into_iter().map(|v| Value::with(v)).collect())
+        Self::Values(values.iter().map(|v| Value::with(v)).collect())
     }
 }
 
@@ -204,12 +249,16 @@ impl Values {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Insert {
     pub schema: Option<String>,
     pub table: String,
     pub columns: Vec<String>,
     pub values: Values,
     pub on_conflict: OnConflict,
+    /// Only valid for Mysql. Names the inserted row (`VALUES (...) AS new`), so updates can refer
+    /// to `new.col` instead of the deprecated `VALUES(col)`. Requires Mysql 8.0.19+.
+    pub row_alias: Option<String>,
     pub returning: Vec<String>,
 }
 
@@ -221,6 +270,7 @@ impl Insert {
             columns: Vec::new(),
             values: Values::DefaultValues,
             on_conflict: OnConflict::default(),
+            row_alias: None,
             returning: Vec::new(),
         }
     }
@@ -261,79 +311,186 @@ impl Insert {
         self
     }
 
+    pub fn row_alias(mut self, alias: &str) -> Self {
+        self.row_alias = Some(alias.to_string());
+        self
+    }
+
     pub fn returning(mut self, returning: &[&str]) -> Self {
         self.returning = returning.iter().map(|r| r.to_string()).collect();
         self
     }
+
+    /// The value a conflicting row would have been inserted with.
+    fn inserted_value(&self, column: &str, dialect: Dialect) -> Expr {
+        match (dialect, &self.row_alias) {
+            (Dialect::Mysql, Some(alias)) => Expr::table_column(alias, column),
+            (Dialect::Mysql, None) => Function::new("VALUES", vec![Expr::column(column)]).into(),
+            _ => Expr::excluded(column),
+        }
+    }
+
+    /// The columns updated by `DoUpdateAllRows`: every inserted column, except ignored columns
+    /// and the conflict target.
+    fn update_all_columns(&self) -> Vec<&String> {
+        let OnConflict::DoUpdateAllRows {
+            conflict,
+            ignore_columns,
+            ..
+        } = &self.on_conflict
+        else {
+            return vec![];
+        };
+        let conflict_columns = conflict.as_columns();
+        self.columns
+            .iter()
+            .filter(|&c| !ignore_columns.contains(c))
+            .filter(|&c| conflict_columns.map(|conflict| !conflict.contains(c)).unwrap_or(true))
+            .collect()
+    }
+
+    /// `DoUpdate` or `DoUpdateAllRows` with nothing to update, rendered like `Ignore`.
+    fn updates_nothing(&self) -> bool {
+        match &self.on_conflict {
+            OnConflict::DoUpdate { updates, .. } => updates.is_empty(),
+            OnConflict::DoUpdateAllRows { .. } => self.update_all_columns().is_empty(),
+            _ => false,
+        }
+    }
+
+    fn write_updates(&self, buf: &mut String, dialect: Dialect) {
+        match &self.on_conflict {
+            OnConflict::DoUpdate { updates, .. } => {
+                let updates: Vec<Expr> = updates
+                    .iter()
+                    .map(|(c, v)| Expr::new_eq(Expr::column(c), v.clone()))
+                    .collect();
+                buf.push_sql_sequence(&updates, ", ", dialect);
+            }
+            OnConflict::DoUpdateAllRows {
+                alternate_values,
+                ..
+            } => {
+                let columns: Vec<Expr> = self
+                    .update_all_columns()
+                    .into_iter()
+                    .map(|c| {
+                        let r = if let Some(v) = alternate_values.get(c) {
+                            v.clone()
+                        } else {
+                            self.inserted_value(c, dialect)
+                        };
+                        Expr::new_eq(Expr::column(c), r)
+                    })
+                    .collect();
+                buf.push_sql_sequence(&columns, ", ", dialect);
+            }
+            _ => return,
+        }
+        if let OnConflict::DoUpdate { where_, .. } | OnConflict::DoUpdateAllRows { where_, .. } =
+            &self.on_conflict
+            && !where_.is_empty()
+        {
+            if dialect == Dialect::Mysql {
+                panic!("Mysql does not support WHERE in ON DUPLICATE KEY UPDATE");
+            }
+            buf.push_str(" WHERE ");
+            buf.push_sql(where_, dialect);
+        }
+    }
+
+    /// Check that the insert can be rendered for the dialect. `to_sql` panics on these errors.
+    pub fn check(&self, dialect: Dialect) -> Result<()> {
+        use OnConflict::*;
+        match (dialect, &self.on_conflict) {
+            (Dialect::Sqlite, DoUpdate { conflict, .. } | DoUpdateAllRows { conflict, .. }) => {
+                if let Conflict::ConstraintName(name) = conflict {
+                    bail!("Sqlite does not support ON CONFLICT ON CONSTRAINT {:?}", name);
+                }
+            }
+            (Dialect::Postgres, Replace) => bail!("Postgres does not support ON CONFLICT REPLACE"),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Like `to_sql`, but returns an error instead of panicking when the insert can't be
+    /// rendered for the dialect.
+    pub fn try_to_sql(&self, dialect: Dialect) -> Result<String> {
+        self.check(dialect)?;
+        Ok(self.to_sql(dialect))
+    }
 }
 
 impl ToSql for Insert {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         use Dialect::*;
         use OnConflict::*;
-        if dialect == Sqlite {
-            match self.on_conflict {
-                Ignore => buf.push_str("INSERT OR IGNORE INTO "),
-                Abort => buf.push_str("INSERT OR ABORT INTO "),
-                Replace => buf.push_str("INSERT OR REPLACE INTO "),
-                DoUpdateAllRows { .. } | DoUpdate { .. } => {
-                    panic!("Sqlite does not support ON CONFLICT DO UPDATE")
-                }
+        match (dialect, &self.on_conflict) {
+            (Sqlite, Ignore) => buf.push_str("INSERT OR IGNORE INTO "),
+            (Sqlite, Abort) => buf.push_str("INSERT OR ABORT INTO "),
+            (Sqlite, Replace) => buf.push_str("INSERT OR REPLACE INTO "),
+            (Mysql, Ignore) => buf.push_str("INSERT IGNORE INTO "),
+            (Mysql, DoUpdate { .. } | DoUpdateAllRows { .. }) if self.updates_nothing() => {
+                buf.push_str("INSERT IGNORE INTO ")
+            }
+            (Mysql, Replace) => buf.push_str("REPLACE INTO "),
+            _ => buf.push_str("INSERT INTO "),
+        }
+        buf.push_table_name(&self.schema, &self.table, dialect);
+        match (&self.values, dialect) {
+            (Values::DefaultValues, Mysql) => buf.push_str(" () "),
+            (Values::DefaultValues, _) => buf.push(' '),
+            _ => {
+                buf.push_str(" (");
+                buf.push_quoted_sequence(&self.columns, ", ", dialect);
+                buf.push_str(") ");
             }
-        } else {
-            buf.push_str("INSERT INTO ");
         }
-        buf.push_table_name(&self.schema, &self.table);
-        buf.push_str(" (");
-        buf.push_quoted_sequence(&self.columns, ", ");
-        buf.push_str(") VALUES ");
+        if let Values::Values(_) = self.values {
+            buf.push_str("VALUES ");
+        }
         self.values.write_sql(buf, dialect);
 
-        if dialect == Postgres {
-            match &self.on_conflict {
-                Ignore => buf.push_str(" ON CONFLICT DO NOTHING"),
-                Abort => {}
-                Replace => panic!("Postgres does not support ON CONFLICT REPLACE"),
-                DoUpdate { conflict, updates } => {
+        if let Err(e) = self.check(dialect) {
+            panic!("{}", e);
+        }
+        match dialect {
+            Postgres | Sqlite => match &self.on_conflict {
+                Ignore if dialect == Postgres => buf.push_str(" ON CONFLICT DO NOTHING"),
+                Ignore | Abort | Replace => {}
+                DoUpdate { conflict, .. } | DoUpdateAllRows { conflict, .. }
+                    if self.updates_nothing() =>
+                {
                     buf.push_str(" ON CONFLICT ");
                     buf.push_sql(conflict, dialect);
-                    buf.push_str(" DO UPDATE SET ");
-                    let updates: Vec<Expr> = updates
-                        .into_iter()
-                        .map(|(c, v)| Expr::new_eq(Expr::column(c), v.clone()))
-                        .collect();
-                    buf.push_sql_sequence(&updates, ", ", dialect);
+                    buf.push_str(" DO NOTHING");
                 }
-                DoUpdateAllRows {
-                    conflict,
-                    alternate_values,
-                    ignore_columns,
-                } => {
+                DoUpdate { conflict, .. } | DoUpdateAllRows { conflict, .. } => {
                     buf.push_str(" ON CONFLICT ");
                     buf.push_sql(conflict, dialect);
                     buf.push_str(" DO UPDATE SET ");
-                    let conflict_columns = conflict.as_columns();
-                    let columns: Vec<Expr> = self
-                        .columns
-                        .iter()
-                        .filter(|&c| !ignore_columns.contains(c))
-                        .filter(|&c| conflict_columns.map(|conflict| !conflict.contains(c)).unwrap_or(true))
-                        .map(|c| {
-                            let r = if let Some(v) = alternate_values.get(c) {
-                                v.clone()
-                            } else {
-                                Expr::excluded(c)
-                            };
-                            Expr::new_eq(Expr::column(c), r)
-                        })
-                        .collect();
-                    buf.push_sql_sequence(&columns, ", ", dialect);
+                    self.write_updates(buf, dialect);
+                }
+            },
+            Mysql => {
+                if let Some(alias) = &self.row_alias {
+                    if matches!(self.values, Values::Select(_) | Values::Compound(_)) {
+                        panic!("Mysql does not support a row alias for INSERT ... SELECT");
+                    }
+                    buf.push_str(" AS ");
+                    buf.push_quoted(alias, dialect);
+                }
+                if let DoUpdate { .. } | DoUpdateAllRows { .. } = self.on_conflict
+                    && !self.updates_nothing()
+                {
+                    buf.push_str(" ON DUPLICATE KEY UPDATE ");
+                    self.write_updates(buf, dialect);
                 }
             }
         }
         if !self.returning.is_empty() {
-            buf.push_str(" RETURNING ");
-            buf.push_quoted_sequence(&self.returning, ", ");
+            push_returning(buf, &self.returning, dialect);
         }
     }
 }
@@ -342,7 +499,7 @@ impl ToSql for Insert {
 mod tests {
     use pretty_assertions::assert_eq;
     use super::*;
-    use crate::query::{Case, Expr};
+    use crate::query::{Case, Expr, Function, Literal};
 
     #[test]
     fn test_basic() {
@@ -352,6 +509,7 @@ mod tests {
             columns: vec!["bar".to_string(), "baz".to_string()],
             values: Values::from(&[&["1", "2"] as &[&str], &["3", "4"]] as &[&[&str]]),
             on_conflict: OnConflict::Abort,
+            row_alias: None,
             returning: vec!["id".to_string()],
         };
         assert_eq!(
@@ -370,6 +528,111 @@ mod tests {
         assert_eq!(insert.to_sql(Dialect::Postgres), expected);
     }
 
+    #[test]
+    fn test_params() {
+        let insert = Insert::new("foo")
+            .columns(&["bar", "baz"])
+            .values(Values::new_value(Value::new().param(1).param("two")))
+            .on_conflict(
+                OnConflict::do_update_all_rows(&["bar"])
+                    .alternate_value("baz", Expr::param("three")),
+            );
+        let (sql, params) = insert.to_sql_with_params(Dialect::Postgres);
+        assert_eq!(
+            sql,
+            r#"INSERT INTO "foo" ("bar", "baz") VALUES ($1, $2) ON CONFLICT ("bar") DO UPDATE SET "baz" = $3"#
+        );
+        assert_eq!(
+            params,
+            vec![Param::Int(1), Param::from("two"), Param::from("three")]
+        );
+    }
+
+    #[test]
+    fn test_mysql_on_duplicate_key() {
+        let insert = Insert::new("foo")
+            .columns(&["id", "bar", "baz", "qux"])
+            .values(Values::new_value(
+                Value::new().param(1).param(2).param(3).param(4),
+            ))
+            .on_conflict(OnConflict::DoUpdateAllRows {
+                conflict: Conflict::columns(["id"]),
+                alternate_values: HashMap::from([("baz".to_string(), Expr::Raw("0".to_string()))]),
+                ignore_columns: vec!["qux".to_string()],
+                where_: Where::And(vec![]),
+            });
+        assert_eq!(
+            insert.to_sql(Dialect::Mysql),
+            "INSERT INTO `foo` (`id`, `bar`, `baz`, `qux`) VALUES (?, ?, ?, ?) ON DUPLICATE KEY UPDATE `bar` = VALUES(`bar`), `baz` = 0"
+        );
+        let insert = insert.row_alias("new");
+        assert_eq!(
+            insert.to_sql(Dialect::Mysql),
+            "INSERT INTO `foo` (`id`, `bar`, `baz`, `qux`) VALUES (?, ?, ?, ?) AS `new` ON DUPLICATE KEY UPDATE `bar` = `new`.`bar`, `baz` = 0"
+        );
+
+        let insert = insert.on_conflict(OnConflict::Ignore);
+        assert_eq!(
+            insert.to_sql(Dialect::Mysql),
+            "INSERT IGNORE INTO `foo` (`id`, `bar`, `baz`, `qux`) VALUES (?, ?, ?, ?) AS `new`"
+        );
+    }
+
+    #[test]
+    fn test_nothing_to_update() {
+        let insert = Insert::new("tags")
+            .columns(&["name"])
+            .values(Values::new_value(Value::new().param("rust")))
+            .on_conflict(OnConflict::do_update_all_rows(&["name"]));
+        assert_eq!(
+            insert.to_sql(Dialect::Postgres),
+            r#"INSERT INTO "tags" ("name") VALUES ($1) ON CONFLICT ("name") DO NOTHING"#
+        );
+        assert_eq!(
+            insert.to_sql(Dialect::Sqlite),
+            r#"INSERT INTO "tags" ("name") VALUES (?) ON CONFLICT ("name") DO NOTHING"#
+        );
+        assert_eq!(
+            insert.to_sql(Dialect::Mysql),
+            "INSERT IGNORE INTO `tags` (`name`) VALUES (?)"
+        );
+
+        let insert = insert.on_conflict(OnConflict::DoUpdate {
+            conflict: Conflict::columns(["name"]),
+            updates: vec![],
+            where_: Where::And(vec![]),
+        });
+        assert_eq!(
+            insert.to_sql(Dialect::Postgres),
+            r#"INSERT INTO "tags" ("name") VALUES ($1) ON CONFLICT ("name") DO NOTHING"#
+        );
+        assert_eq!(
+            insert.to_sql(Dialect::Mysql),
+            "INSERT IGNORE INTO `tags` (`name`) VALUES (?)"
+        );
+    }
+
+    #[test]
+    fn test_sqlite_upsert() {
+        let insert = Insert::new("foo")
+            .columns(&["id", "bar"])
+            .values(Values::new_value(Value::new().param(1).param(2)));
+        let deleted = Expr::is_null(Expr::table_column("foo", "deleted_at"));
+        let insert = insert.on_conflict(OnConflict::do_update_on_pkey("id").where_(Where::Expr(deleted)));
+        assert_eq!(
+            insert.to_sql(Dialect::Sqlite),
+            r#"INSERT INTO "foo" ("id", "bar") VALUES (?, ?) ON CONFLICT ("id") DO UPDATE SET "bar" = excluded."bar" WHERE "foo"."deleted_at" IS NULL"#
+        );
+
+        let insert = insert.on_conflict(OnConflict::DoUpdate {
+            conflict: Conflict::ConstraintName("foo_pkey".to_string()),
+            updates: vec![("bar".to_string(), Expr::excluded("bar"))],
+            where_: Where::And(vec![]),
+        });
+        assert!(insert.try_to_sql(Dialect::Sqlite).is_err());
+        assert!(insert.try_to_sql(Dialect::Postgres).is_ok());
+    }
+
     #[test]
     fn test_override() {
         let columns = &["id", "name", "email"];
@@ -394,12 +657,13 @@ mod tests {
         let insert = Insert::new("users")
             .columns(columns)
             .column("updated_at")
-            .values(Values::new_value(Value::with(&[
-                "1",
-                "Kurt",
-                "test@example.com",
-                "NOW()",
-            ])))
+            .values(Values::new_value(
+                Value::new()
+                    .expr(Literal::from(1))
+                    .expr(Literal::from("Kurt"))
+                    .expr(Literal::from("test@example.com"))
+                    .expr(Function::now()),
+            ))
             .on_conflict(
                 OnConflict::do_update_on_pkey("id")
                     .alternate_value("updated_at", on_conflict_update_value),
@@ -407,7 +671,7 @@ mod tests {
         let sql = insert.to_sql(Dialect::Postgres);
         let expected = r#"
 INSERT INTO "users" ("id", "name", "email", "updated_at") VALUES
-(1, Kurt, test@example.com, NOW())
+(1, 'Kurt', 'test@example.com', now())
 ON CONFLICT ("id") DO UPDATE SET
 "name" = excluded."name",
 "email" = excluded."email",
@@ -1,10 +1,12 @@
 mod alter_table;
+mod compound;
 mod create_schema;
 mod cte;
 mod delete;
 mod drop_table;
 mod insert;
 mod select;
+mod tenant;
 mod union;
 mod update;
 
@@ -12,7 +14,10 @@ pub use insert::*;
 pub use select::*;
 pub use update::*;
 pub use alter_table::*;
+pub use compound::*;
 pub use create_schema::*;
 pub use cte::*;
+pub use delete::*;
 pub use drop_table::*;
+pub use tenant::*;
 pub use union::*;
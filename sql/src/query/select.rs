@@ -1,66 +1,92 @@
 use crate::query::{Cte, CteQuery};
+use crate::query::cte::write_ctes;
 use crate::util::SqlExtension;
 use crate::{Dialect, ToSql};
 
 mod expr;
+mod from;
+mod function;
 mod join;
+mod keyset;
+mod literal;
+mod lock;
+mod window;
 
 pub use expr::*;
+pub use from::*;
+pub use function::*;
 pub use join::*;
+pub use keyset::*;
+pub use literal::*;
+pub use lock::*;
+pub use window::*;
 
 /// A SELECT query.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Select {
     pub ctes: Vec<Cte>,
-    pub distinct: bool,
+    pub distinct: Distinct,
     pub columns: Vec<SelectColumn>,
     pub from: Option<From>,
     pub join: Vec<Join>,
     pub where_: Where,
     pub group: Vec<GroupBy>,
     pub having: Where,
+    pub windows: Vec<Window>,
     pub order: Vec<OrderBy>,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
+    pub locks: Vec<Lock>,
 }
 
 impl Default for Select {
     fn default() -> Self {
         Self {
             ctes: vec![],
-            distinct: false,
+            distinct: Distinct::None,
             columns: vec![],
             from: None,
             join: vec![],
             where_: Where::And(vec![]),
             group: vec![],
             having: Where::And(vec![]),
+            windows: vec![],
             order: vec![],
             limit: None,
             offset: None,
+            locks: vec![],
         }
     }
 }
 
 impl Select {
     pub fn with_raw(mut self, name: &str, query: &str) -> Self {
-        self.ctes.push(Cte {
-            name: name.to_string(),
-            query: CteQuery::Raw(query.to_string()),
-        });
+        self.ctes.push(Cte::new(name, CteQuery::Raw(query.to_string())));
         self
     }
 
-    pub fn with(mut self, name: &str, query: Select) -> Self {
-        self.ctes.push(Cte {
-            name: name.to_string(),
-            query: CteQuery::Select(query),
-        });
+    pub fn with(mut self, name: &str, query: impl Into<CteQuery>) -> Self {
+        self.ctes.push(Cte::new(name, query));
+        self
+    }
+
+    pub fn with_cte(mut self, cte: Cte) -> Self {
+        self.ctes.push(cte);
         self
     }
 
     pub fn distinct(mut self) -> Self {
-        self.distinct = true;
+        self.distinct = Distinct::All;
+        self
+    }
+
+    /// Only valid for Postgres. Keeps the first row of each group, as ordered by ORDER BY.
+    pub fn distinct_on(mut self, expr: impl Into<Expr>) -> Self {
+        match &mut self.distinct {
+            Distinct::On(exprs) => exprs.push(expr.into()),
+            _ => self.distinct = Distinct::On(vec![expr.into()]),
+        }
         self
     }
 
@@ -69,6 +95,17 @@ impl Select {
         self
     }
 
+    pub fn select(mut self, column: impl Into<SelectColumn>) -> Self {
+        self.columns.push(column.into());
+        self
+    }
+
+    pub fn select_expr(mut self, expression: impl Into<Expr>, alias: &str) -> Self {
+        self.columns
+            .push(SelectColumn::expr(expression).alias(alias));
+        self
+    }
+
     pub fn select_raw(mut self, expression: impl Into<String>) -> Self {
         self.columns.push(SelectColumn {
             expression: SelectExpression::Raw(expression.into()),
@@ -77,12 +114,8 @@ impl Select {
         self
     }
 
-    pub fn from(mut self, table: &str) -> Self {
-        self.from = Some(From {
-            schema: None,
-            table: table.to_string(),
-            alias: None,
-        });
+    pub fn from(mut self, from: impl Into<From>) -> Self {
+        self.from = Some(from.into());
         self
     }
 
@@ -105,7 +138,12 @@ impl Select {
     }
 
     pub fn group_by(mut self, group: &str) -> Self {
-        self.group.push(GroupBy(group.to_string()));
+        self.group.push(GroupBy::Expr(Expr::Raw(group.to_string())));
+        self
+    }
+
+    pub fn group_by_expr(mut self, group: impl Into<GroupBy>) -> Self {
+        self.group.push(group.into());
         self
     }
 
@@ -117,6 +155,14 @@ impl Select {
         self
     }
 
+    pub fn window(mut self, name: &str, over: Over) -> Self {
+        self.windows.push(Window {
+            name: name.to_string(),
+            over,
+        });
+        self
+    }
+
     pub fn order_by(mut self, order: OrderBy) -> Self {
         self.order.push(order);
         self
@@ -139,21 +185,62 @@ impl Select {
         self.offset = Some(offset);
         self
     }
+
+    pub fn lock(mut self, lock: Lock) -> Self {
+        self.locks.push(lock);
+        self
+    }
+
+    pub fn for_update(self) -> Self {
+        self.lock(Lock::update())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Distinct {
+    #[default]
+    None,
+    /// `DISTINCT`
+    All,
+    /// `DISTINCT ON (...)`. Only valid for Postgres; on Mysql and Sqlite, rewrite the query
+    /// with `row_number() OVER (PARTITION BY ...)` instead.
+    On(Vec<Expr>),
+}
+
+impl ToSql for Distinct {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        match self {
+            Distinct::None => {}
+            Distinct::All => buf.push_str("DISTINCT "),
+            Distinct::On(exprs) => {
+                if dialect != Dialect::Postgres {
+                    panic!("{:?} does not support DISTINCT ON", dialect);
+                }
+                buf.push_str("DISTINCT ON (");
+                buf.push_sql_sequence(exprs, ", ", dialect);
+                buf.push_str(") ");
+            }
+        }
+    }
 }
 
 /// Represents a select column value.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SelectExpression {
     Column {
         schema: Option<String>,
         table: Option<String>,
         column: String,
     },
+    Expr(Expr),
     Raw(String),
 }
 
 /// Represents a column of a SELECT statement.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectColumn {
     pub expression: SelectExpression,
     pub alias: Option<String>,
@@ -163,6 +250,7 @@ impl SelectColumn {
     pub fn column(&self) -> Option<&str> {
         match &self.expression {
             SelectExpression::Column { column, .. } => Some(column),
+            SelectExpression::Expr(Expr::Column { column, .. }) => Some(column),
             _ => None,
         }
     }
@@ -189,6 +277,13 @@ impl SelectColumn {
         }
     }
 
+    pub fn expr(expression: impl Into<Expr>) -> Self {
+        Self {
+            expression: SelectExpression::Expr(expression.into()),
+            alias: None,
+        }
+    }
+
     pub fn raw(expression: &str) -> Self {
         Self {
             expression: SelectExpression::Raw(expression.to_string()),
@@ -215,8 +310,20 @@ impl<T: Into<String>> std::convert::From<T> for SelectColumn {
     }
 }
 
+impl std::convert::From<Expr> for SelectColumn {
+    fn from(expression: Expr) -> Self {
+        Self::expr(expression)
+    }
+}
+
+impl std::convert::From<Function> for SelectColumn {
+    fn from(function: Function) -> Self {
+        Self::expr(function)
+    }
+}
+
 impl ToSql for SelectColumn {
-    fn write_sql(&self, buf: &mut String, _: Dialect) {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         use SelectExpression::*;
         match &self.expression {
             Column {
@@ -225,14 +332,17 @@ impl ToSql for SelectColumn {
                 column,
             } => {
                 if let Some(schema) = schema {
-                    buf.push_quoted(schema);
+                    buf.push_quoted(schema, dialect);
                     buf.push('.');
                 }
                 if let Some(table) = table {
-                    buf.push_quoted(table);
+                    buf.push_quoted(table, dialect);
                     buf.push('.');
                 }
-                buf.push_quoted(column);
+                buf.push_quoted(column, dialect);
+            }
+            Expr(expr) => {
+                buf.push_sql(expr, dialect);
             }
             Raw(raw) => {
                 buf.push_str(raw);
@@ -240,39 +350,13 @@ impl ToSql for SelectColumn {
         }
         if let Some(alias) = &self.alias {
             buf.push_str(" AS ");
-            buf.push_quoted(alias);
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct From {
-    pub schema: Option<String>,
-    pub table: String,
-    pub alias: Option<String>,
-}
-
-impl<T: Into<String>> std::convert::From<T> for From {
-    fn from(table: T) -> Self {
-        Self {
-            schema: None,
-            table: table.into(),
-            alias: None,
-        }
-    }
-}
-
-impl ToSql for From {
-    fn write_sql(&self, buf: &mut String, _: Dialect) {
-        buf.push_table_name(&self.schema, &self.table);
-        if let Some(alias) = &self.alias {
-            buf.push_str(" AS ");
-            buf.push_quoted(alias);
+            buf.push_quoted(alias, dialect);
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Where {
     And(Vec<Where>),
     Or(Vec<Where>),
@@ -287,6 +371,12 @@ impl std::convert::From<String> for Where {
     }
 }
 
+impl std::convert::From<Expr> for Where {
+    fn from(expr: Expr) -> Self {
+        Where::Expr(expr)
+    }
+}
+
 impl Where {
     pub fn is_empty(&self) -> bool {
         use Where::*;
@@ -308,11 +398,11 @@ impl ToSql for Where {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         match self {
             Where::And(v) => {
-                buf.push_sql_sequence(&v, " AND ", dialect);
+                buf.push_sql_sequence(v, " AND ", dialect);
             }
             Where::Or(v) => {
                 buf.push('(');
-                buf.push_sql_sequence(&v, " OR ", dialect);
+                buf.push_sql_sequence(v, " OR ", dialect);
                 buf.push(')');
             }
             #[allow(deprecated)]
@@ -327,19 +417,23 @@ impl ToSql for Where {
 }
 
 /// The direction of a column in an ORDER BY clause.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
+    #[default]
     Asc,
     Desc,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NullsOrder {
     First,
     Last,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OrderBy {
     pub column: String,
     pub direction: Option<Direction>,
@@ -405,46 +499,103 @@ impl ToSql for OrderBy {
     }
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Direction::Asc
+/// An item in the GROUP BY clause.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GroupBy {
+    Expr(Expr),
+    /// Group by the nth select column, starting at 1.
+    Position(usize),
+    /// On Mysql, rendered as `a, b WITH ROLLUP`, and must be the only item. Not supported by
+    /// Sqlite.
+    Rollup(Vec<Expr>),
+    /// Only valid for Postgres
+    Cube(Vec<Expr>),
+    /// Only valid for Postgres
+    GroupingSets(Vec<Vec<Expr>>),
+}
+
+impl GroupBy {
+    pub fn rollup(exprs: Vec<Expr>) -> Self {
+        GroupBy::Rollup(exprs)
+    }
+
+    pub fn cube(exprs: Vec<Expr>) -> Self {
+        GroupBy::Cube(exprs)
+    }
+
+    pub fn grouping_sets(sets: Vec<Vec<Expr>>) -> Self {
+        GroupBy::GroupingSets(sets)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct GroupBy(String);
+impl<T: Into<Expr>> std::convert::From<T> for GroupBy {
+    fn from(expr: T) -> Self {
+        GroupBy::Expr(expr.into())
+    }
+}
 
 impl ToSql for GroupBy {
-    fn write_sql(&self, buf: &mut String, _: Dialect) {
-        buf.push_str(&self.0)
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        match (self, dialect) {
+            (GroupBy::Expr(expr), _) => buf.push_sql(expr, dialect),
+            (GroupBy::Position(n), _) => buf.push_str(&n.to_string()),
+            (GroupBy::Rollup(exprs), Dialect::Mysql) => {
+                buf.push_sql_sequence(exprs, ", ", dialect);
+                buf.push_str(" WITH ROLLUP");
+            }
+            (GroupBy::Rollup(exprs), Dialect::Postgres) => {
+                buf.push_str("ROLLUP (");
+                buf.push_sql_sequence(exprs, ", ", dialect);
+                buf.push(')');
+            }
+            (GroupBy::Cube(exprs), Dialect::Postgres) => {
+                buf.push_str("CUBE (");
+                buf.push_sql_sequence(exprs, ", ", dialect);
+                buf.push(')');
+            }
+            (GroupBy::GroupingSets(sets), Dialect::Postgres) => {
+                buf.push_str("GROUPING SETS (");
+                for (i, set) in sets.iter().enumerate() {
+                    if i > 0 {
+                        buf.push_str(", ");
+                    }
+                    buf.push('(');
+                    buf.push_sql_sequence(set, ", ", dialect);
+                    buf.push(')');
+                }
+                buf.push(')');
+            }
+            (_, dialect) => panic!("{:?} does not support {:?}", dialect, self),
+        }
     }
 }
 
 impl ToSql for Select {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
-        if !self.ctes.is_empty() {
-            buf.push_str("WITH ");
-            buf.push_sql_sequence(&self.ctes, ", ", dialect);
-            buf.push(' ');
-        }
+        write_ctes(buf, &self.ctes, dialect);
         buf.push_str("SELECT ");
-        if self.distinct {
-            buf.push_str("DISTINCT ");
-        }
+        buf.push_sql(&self.distinct, dialect);
         buf.push_sql_sequence(&self.columns, ", ", dialect);
         if let Some(from) = &self.from {
             buf.push_str(" FROM ");
             buf.push_str(&from.to_sql(dialect));
-            buf.push(' ');
         }
-        if !self.join.is_empty() {
-            buf.push_sql_sequence(&self.join, " ", dialect);
+        for join in &self.join {
+            buf.push(' ');
+            buf.push_sql(join, dialect);
         }
         if !self.where_.is_empty() {
             buf.push_str(" WHERE ");
             buf.push_str(&self.where_.to_sql(dialect));
         }
         if !self.group.is_empty() {
+            if dialect == Dialect::Mysql
+                && self.group.len() > 1
+                && self.group.iter().any(|g| matches!(g, GroupBy::Rollup(_)))
+            {
+                panic!("Mysql only supports WITH ROLLUP as the only GROUP BY item");
+            }
             buf.push_str(" GROUP BY ");
             buf.push_sql_sequence(&self.group, ", ", dialect);
         }
@@ -452,6 +603,10 @@ impl ToSql for Select {
             buf.push_str(" HAVING ");
             buf.push_str(&self.having.to_sql(dialect));
         }
+        if !self.windows.is_empty() {
+            buf.push_str(" WINDOW ");
+            buf.push_sql_sequence(&self.windows, ", ", dialect);
+        }
         if !self.order.is_empty() {
             buf.push_str(" ORDER BY ");
             buf.push_sql_sequence(&self.order, ", ", dialect);
@@ -464,6 +619,10 @@ impl ToSql for Select {
             buf.push_str(" OFFSET ");
             buf.push_str(&offset.to_string());
         }
+        for lock in &self.locks {
+            buf.push(' ');
+            buf.push_sql(lock, dialect);
+        }
     }
 }
 
@@ -487,7 +646,142 @@ mod tests {
             .offset(5);
         assert_eq!(
             select.to_sql(Dialect::Postgres),
-            r#"WITH foo AS (SELECT 1), bar AS (SELECT 1) SELECT id, name FROM "users" JOIN "posts" ON users.id = posts.user_id WHERE 1=1 ORDER BY id ASC, name DESC LIMIT 10 OFFSET 5"#
+            r#"WITH "foo" AS (SELECT 1), "bar" AS (SELECT 1) SELECT id, name FROM "users" JOIN "posts" ON users.id = posts.user_id WHERE 1=1 ORDER BY id ASC, name DESC LIMIT 10 OFFSET 5"#
+        );
+    }
+
+    #[test]
+    fn test_select_expressions() {
+        let select = Select::default()
+            .table_column("users", "team_id")
+            .select_expr(Function::count_star(), "users")
+            .select_expr(
+                Function::coalesce(vec![
+                    Function::sum(Expr::column("amount")).into(),
+                    Expr::Raw("0".to_string()),
+                ]),
+                "total",
+            )
+            .from("users")
+            .group_by("team_id");
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT "users"."team_id", count(*) AS "users", coalesce(sum("amount"), 0) AS "total" FROM "users" GROUP BY team_id"#
+        );
+    }
+
+    #[test]
+    fn test_window() {
+        let select = Select::default()
+            .select_raw("id")
+            .select_expr(Function::row_number().over(Over::window("w")), "rn")
+            .from("events")
+            .window(
+                "w",
+                Over::new()
+                    .partition_by(Expr::column("user_id"))
+                    .order_by(OrderBy::new("created_at").desc()),
+            )
+            .order_asc("id");
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT id, row_number() OVER "w" AS "rn" FROM "events" WINDOW "w" AS (PARTITION BY "user_id" ORDER BY created_at DESC) ORDER BY id ASC"#
+        );
+    }
+
+    #[test]
+    fn test_params_across_ctes() {
+        let active = Select::default()
+            .select_raw("id")
+            .from("users")
+            .where_(Where::Expr(Expr::new_eq(
+                Expr::column("status"),
+                Expr::param("active"),
+            )));
+        let select = Select::default()
+            .with("active", active)
+            .select_raw("count(*)")
+            .from("active")
+            .where_(Where::Expr(Expr::new_eq(
+                Expr::column("team_id"),
+                Expr::param(7),
+            )));
+        let (sql, params) = select.to_sql_with_params(Dialect::Postgres);
+        assert_eq!(
+            sql,
+            r#"WITH "active" AS (SELECT id FROM "users" WHERE "status" = $1) SELECT count(*) FROM "active" WHERE "team_id" = $2"#
+        );
+        assert_eq!(
+            params,
+            vec![crate::Param::from("active"), crate::Param::Int(7)]
+        );
+    }
+
+    #[test]
+    fn test_group_by() {
+        let select = Select::default()
+            .select_raw("region")
+            .select_raw("product")
+            .select(Function::sum(Expr::column("amount")))
+            .select(Function::grouping(vec![Expr::column("region")]))
+            .from("sales")
+            .group_by_expr(GroupBy::rollup(vec![
+                Expr::column("region"),
+                Expr::column("product"),
+            ]));
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT region, product, sum("amount"), grouping("region") FROM "sales" GROUP BY ROLLUP ("region", "product")"#
+        );
+        assert_eq!(
+            select.to_sql(Dialect::Mysql),
+            "SELECT region, product, sum(`amount`), grouping(`region`) FROM `sales` GROUP BY `region`, `product` WITH ROLLUP"
+        );
+
+        let select = Select::default()
+            .select_raw("count(*)")
+            .from("sales")
+            .group_by_expr(GroupBy::Position(1))
+            .group_by_expr(GroupBy::grouping_sets(vec![
+                vec![Expr::column("region")],
+                vec![],
+            ]));
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT count(*) FROM "sales" GROUP BY 1, GROUPING SETS (("region"), ())"#
+        );
+    }
+
+    #[test]
+    fn test_distinct_on() {
+        let select = Select::default()
+            .distinct_on(Expr::column("user_id"))
+            .select_raw("*")
+            .from("events")
+            .order_asc("user_id")
+            .order_desc("created_at");
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT DISTINCT ON ("user_id") * FROM "events" ORDER BY user_id ASC, created_at DESC"#
+        );
+    }
+
+    #[test]
+    fn test_locking() {
+        let select = Select::default()
+            .select_raw("*")
+            .from("jobs")
+            .join(Join::new("queues").on_raw("queues.id = jobs.queue_id"))
+            .order_asc("id")
+            .limit(1)
+            .lock(Lock::update().of("jobs").skip_locked());
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT * FROM "jobs" JOIN "queues" ON queues.id = jobs.queue_id ORDER BY id ASC LIMIT 1 FOR UPDATE OF "jobs" SKIP LOCKED"#
+        );
+        assert_eq!(
+            select.to_sql(Dialect::Mysql),
+            "SELECT * FROM `jobs` JOIN `queues` ON queues.id = jobs.queue_id ORDER BY id ASC LIMIT 1 FOR UPDATE OF `jobs` SKIP LOCKED"
         );
     }
 }
@@ -1,11 +1,13 @@
+use crate::query::{Function, Literal};
+use crate::to_sql::push_param;
 use crate::util::SqlExtension;
-use crate::{Dialect, ToSql};
+use crate::{Dialect, Param, Select, ToSql, Type};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Case {
-    cases: Vec<(Expr, Expr)>,
-    els: Option<Box<Expr>>,
+    pub(crate) cases: Vec<(Expr, Expr)>,
+    pub(crate) els: Option<Box<Expr>>,
 }
 
 impl Case {
@@ -48,10 +50,46 @@ impl ToSql for Case {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operation {
     Eq,
+    NotEq,
     Gte,
     Lte,
     Gt,
     Lt,
+    Like,
+    NotLike,
+    /// Emulated as `LOWER(l) LIKE LOWER(r)` on Mysql and Sqlite.
+    ILike,
+    /// Emulated as `LOWER(l) NOT LIKE LOWER(r)` on Mysql and Sqlite.
+    NotILike,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    /// String concatenation. Rendered as `CONCAT(l, r)` on Mysql.
+    Concat,
+}
+
+impl Operation {
+    /// Mysql and Sqlite give `LIKE` the same precedence as `=`, while Postgres binds it tighter.
+    /// Sqlite binds `||` tighter than any other binary operator, Postgres looser than `+` and `-`.
+    fn precedence(&self, dialect: Dialect) -> u8 {
+        use Operation::*;
+        match self {
+            Concat if dialect == Dialect::Sqlite => 10,
+            Mul | Div | Mod => 9,
+            Add | Sub => 8,
+            Concat => 7,
+            Like | NotLike | ILike | NotILike if dialect == Dialect::Postgres => 6,
+            Like | NotLike | ILike | NotILike => 5,
+            Eq | NotEq | Gte | Lte | Gt | Lt => 5,
+        }
+    }
+
+    fn is_left_associative(&self) -> bool {
+        use Operation::*;
+        matches!(self, Mul | Div | Mod | Add | Sub | Concat)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -67,11 +105,38 @@ pub enum Expr {
         column: String,
     },
     BinOp(Operation, Box<Expr>, Box<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    /// Unary minus
+    Neg(Box<Expr>),
+    IsNull(Box<Expr>),
+    IsNotNull(Box<Expr>),
+    In(Box<Expr>, Vec<Expr>),
+    NotIn(Box<Expr>, Vec<Expr>),
+    Between(Box<Expr>, Box<Expr>, Box<Expr>),
+    NotBetween(Box<Expr>, Box<Expr>, Box<Expr>),
+    /// A bound parameter. Rendered as a placeholder, and collected by `ToSql::to_sql_with_params`.
+    Param(Param),
+    Function(Function),
+    Exists(Box<Select>),
+    InSelect(Box<Expr>, Box<Select>),
+    NotInSelect(Box<Expr>, Box<Select>),
+    /// A scalar subquery
+    Subquery(Box<Select>),
+    /// `l op ANY (SELECT ...)`. On Sqlite, only `= ANY` is supported, rendered as `IN`.
+    Any(Operation, Box<Expr>, Box<Select>),
+    /// `l op ALL (SELECT ...)`. On Sqlite, only `<> ALL` is supported, rendered as `NOT IN`.
+    All(Operation, Box<Expr>, Box<Select>),
+    Literal(Literal),
+    /// Rendered as `x::type` on Postgres, and `CAST(x AS type)` on Mysql and Sqlite.
+    Cast(Box<Expr>, Type),
+    /// A row value, e.g. `(a, b)`, as used in row comparisons like `(a, b) > (1, 2)`.
+    Row(Vec<Expr>),
 }
 
 impl Expr {
     pub fn excluded(column: &str) -> Self {
-        Self::Raw(format!("excluded.\"{}\"", column))
+        Self::Raw(format!("excluded.\"{}\"", column.replace('"', "\"\"")))
     }
 
     pub fn column(column: &str) -> Self {
@@ -82,10 +147,46 @@ impl Expr {
         }
     }
 
+    pub fn param(value: impl Into<Param>) -> Self {
+        Self::Param(value.into())
+    }
+
+    pub fn literal(value: impl Into<Literal>) -> Self {
+        Self::Literal(value.into())
+    }
+
+    pub fn row(exprs: Vec<Expr>) -> Self {
+        Self::Row(exprs)
+    }
+
+    pub fn null() -> Self {
+        Self::Literal(Literal::Null)
+    }
+
+    pub fn cast<E: Into<Expr>>(expr: E, typ: Type) -> Self {
+        Self::Cast(Box::new(expr.into()), typ)
+    }
+
     pub fn new_eq<L: Into<Expr>, R: Into<Expr>>(left: L, right: R) -> Self {
         Self::BinOp(Operation::Eq, Box::new(left.into()), Box::new(right.into()))
     }
 
+    pub fn binop<L: Into<Expr>, R: Into<Expr>>(op: Operation, left: L, right: R) -> Self {
+        Self::BinOp(op, Box::new(left.into()), Box::new(right.into()))
+    }
+
+    pub fn like<L: Into<Expr>, R: Into<Expr>>(left: L, pattern: R) -> Self {
+        Self::binop(Operation::Like, left, pattern)
+    }
+
+    pub fn ilike<L: Into<Expr>, R: Into<Expr>>(left: L, pattern: R) -> Self {
+        Self::binop(Operation::ILike, left, pattern)
+    }
+
+    pub fn concat<L: Into<Expr>, R: Into<Expr>>(left: L, right: R) -> Self {
+        Self::binop(Operation::Concat, left, right)
+    }
+
     pub fn table_column(table: &str, column: &str) -> Self {
         Self::Column {
             schema: None,
@@ -106,6 +207,109 @@ impl Expr {
         Self::And(and)
     }
 
+    pub fn new_or(or: Vec<Expr>) -> Self {
+        Self::Or(or)
+    }
+
+    pub fn not<E: Into<Expr>>(expr: E) -> Self {
+        Self::Not(Box::new(expr.into()))
+    }
+
+    pub fn is_null<E: Into<Expr>>(expr: E) -> Self {
+        Self::IsNull(Box::new(expr.into()))
+    }
+
+    pub fn is_not_null<E: Into<Expr>>(expr: E) -> Self {
+        Self::IsNotNull(Box::new(expr.into()))
+    }
+
+    pub fn in_list<E: Into<Expr>>(expr: E, list: Vec<Expr>) -> Self {
+        Self::In(Box::new(expr.into()), list)
+    }
+
+    pub fn not_in_list<E: Into<Expr>>(expr: E, list: Vec<Expr>) -> Self {
+        Self::NotIn(Box::new(expr.into()), list)
+    }
+
+    pub fn between<E: Into<Expr>, L: Into<Expr>, H: Into<Expr>>(expr: E, low: L, high: H) -> Self {
+        Self::Between(
+            Box::new(expr.into()),
+            Box::new(low.into()),
+            Box::new(high.into()),
+        )
+    }
+
+    pub fn not_between<E: Into<Expr>, L: Into<Expr>, H: Into<Expr>>(
+        expr: E,
+        low: L,
+        high: H,
+    ) -> Self {
+        Self::NotBetween(
+            Box::new(expr.into()),
+            Box::new(low.into()),
+            Box::new(high.into()),
+        )
+    }
+
+    pub fn exists(select: Select) -> Self {
+        Self::Exists(Box::new(select))
+    }
+
+    pub fn in_select<E: Into<Expr>>(expr: E, select: Select) -> Self {
+        Self::InSelect(Box::new(expr.into()), Box::new(select))
+    }
+
+    pub fn not_in_select<E: Into<Expr>>(expr: E, select: Select) -> Self {
+        Self::NotInSelect(Box::new(expr.into()), Box::new(select))
+    }
+
+    pub fn subquery(select: Select) -> Self {
+        Self::Subquery(Box::new(select))
+    }
+
+    pub fn any<E: Into<Expr>>(op: Operation, expr: E, select: Select) -> Self {
+        Self::Any(op, Box::new(expr.into()), Box::new(select))
+    }
+
+    pub fn all<E: Into<Expr>>(op: Operation, expr: E, select: Select) -> Self {
+        Self::All(op, Box::new(expr.into()), Box::new(select))
+    }
+
+    /// Binding strength when rendered, used to decide where parentheses are needed.
+    /// `Raw` is treated as atomic, so wrap it in parentheses yourself if it is not.
+    fn precedence(&self, dialect: Dialect) -> u8 {
+        match self {
+            Expr::Not(_) => 3,
+            Expr::IsNull(_) | Expr::IsNotNull(_) | Expr::NotDistinctFrom(..) => 4,
+            // Mysql and Sqlite give these the same precedence as comparisons.
+            Expr::In(..)
+            | Expr::NotIn(..)
+            | Expr::InSelect(..)
+            | Expr::NotInSelect(..)
+            | Expr::Between(..)
+            | Expr::NotBetween(..)
+                if dialect == Dialect::Postgres =>
+            {
+                6
+            }
+            Expr::In(..)
+            | Expr::NotIn(..)
+            | Expr::InSelect(..)
+            | Expr::NotInSelect(..)
+            | Expr::Between(..)
+            | Expr::NotBetween(..) => 5,
+            Expr::Any(..) | Expr::All(..) => 5,
+            Expr::BinOp(Operation::Concat, ..) if dialect == Dialect::Mysql => u8::MAX,
+            Expr::BinOp(op, ..) => op.precedence(dialect),
+            Expr::Neg(_) => 11,
+            _ => u8::MAX,
+        }
+    }
+
+    pub fn function(name: &str, args: Vec<Expr>) -> Self {
+        Self::Function(Function::new(name, args))
+    }
+
     pub fn case(case: Case) -> Self {
         Self::Case(case)
     }
@@ -115,9 +319,9 @@ impl Expr {
     }
 }
 
-impl Into<Expr> for &str {
-    fn into(self) -> Expr {
-        Expr::Raw(self.to_string())
+impl From<&str> for Expr {
+    fn from(s: &str) -> Self {
+        Expr::Raw(s.to_string())
     }
 }
 
@@ -139,9 +343,13 @@ impl ToSql for Expr {
             }
             Expr::Raw(a) => buf.push_str(a),
             Expr::NotDistinctFrom(l, r) => {
-                buf.push_sql(l.as_ref(), dialect);
-                buf.push_str(" IS NOT DISTINCT FROM ");
-                buf.push_sql(r.as_ref(), dialect);
+                write_operand(buf, l, 5, dialect);
+                match dialect {
+                    Dialect::Postgres => buf.push_str(" IS NOT DISTINCT FROM "),
+                    Dialect::Mysql => buf.push_str(" <=> "),
+                    Dialect::Sqlite => buf.push_str(" IS "),
+                }
+                write_operand(buf, r, 5, dialect);
             }
             Expr::Column {
                 schema,
@@ -149,32 +357,222 @@ impl ToSql for Expr {
                 column,
             } => {
                 if let Some(schema) = schema {
-                    buf.push_quoted(schema);
+                    buf.push_quoted(schema, dialect);
                     buf.push('.');
                 }
                 if let Some(table) = table {
-                    buf.push_quoted(table);
+                    buf.push_quoted(table, dialect);
                     buf.push('.');
                 }
-                buf.push_quoted(column);
+                buf.push_quoted(column, dialect);
             }
-            Expr::BinOp(op, l, r) => {
+            Expr::BinOp(Operation::Concat, l, r) if dialect == Dialect::Mysql => {
+                buf.push_str("CONCAT(");
                 buf.push_sql(l.as_ref(), dialect);
+                buf.push_str(", ");
+                buf.push_sql(r.as_ref(), dialect);
+                buf.push(')');
+            }
+            Expr::BinOp(op @ (Operation::ILike | Operation::NotILike), l, r)
+                if dialect != Dialect::Postgres =>
+            {
+                buf.push_str("LOWER(");
+                buf.push_sql(l.as_ref(), dialect);
+                buf.push(')');
                 buf.push_sql(op, dialect);
+                buf.push_str("LOWER(");
                 buf.push_sql(r.as_ref(), dialect);
+                buf.push(')');
+            }
+            Expr::BinOp(op, l, r) => {
+                let prec = op.precedence(dialect);
+                if op.is_left_associative() {
+                    write_operand(buf, l, prec, dialect);
+                } else {
+                    write_operand(buf, l, prec + 1, dialect);
+                }
+                buf.push_sql(op, dialect);
+                write_operand(buf, r, prec + 1, dialect);
+            }
+            Expr::Or(or) => {
+                buf.push('(');
+                for (i, expr) in or.iter().enumerate() {
+                    if i > 0 {
+                        buf.push_str(" OR ");
+                    }
+                    buf.push('(');
+                    expr.write_sql(buf, dialect);
+                    buf.push(')');
+                }
+                buf.push(')');
+            }
+            Expr::Not(e) => {
+                buf.push_str("NOT ");
+                write_operand(buf, e, 3, dialect);
+            }
+            Expr::Neg(e) => {
+                buf.push('-');
+                let start = buf.len();
+                write_operand(buf, e, 11, dialect);
+                // `--` would start a line comment.
+                if buf[start..].starts_with('-') {
+                    buf.insert(start, ' ');
+                }
+            }
+            Expr::IsNull(e) => {
+                write_operand(buf, e, 5, dialect);
+                buf.push_str(" IS NULL");
+            }
+            Expr::IsNotNull(e) => {
+                write_operand(buf, e, 5, dialect);
+                buf.push_str(" IS NOT NULL");
+            }
+            Expr::In(e, list) | Expr::NotIn(e, list) => {
+                let negated = matches!(self, Expr::NotIn(..));
+                // `IN ()` is a syntax error, but an empty list is a valid (if trivial) filter.
+                if list.is_empty() {
+                    buf.push_str(if negated { "TRUE" } else { "FALSE" });
+                    return;
+                }
+                write_operand(buf, e, 7, dialect);
+                buf.push_str(if negated { " NOT IN (" } else { " IN (" });
+                buf.push_sql_sequence(list, ", ", dialect);
+                buf.push(')');
+            }
+            Expr::Between(e, low, high) | Expr::NotBetween(e, low, high) => {
+                write_operand(buf, e, 7, dialect);
+                if matches!(self, Expr::NotBetween(..)) {
+                    buf.push_str(" NOT BETWEEN ");
+                } else {
+                    buf.push_str(" BETWEEN ");
+                }
+                write_operand(buf, low, 7, dialect);
+                buf.push_str(" AND ");
+                write_operand(buf, high, 7, dialect);
+            }
+            Expr::Param(p) => push_param(buf, p, dialect),
+            Expr::Row(exprs) => {
+                buf.push('(');
+                buf.push_sql_sequence(exprs, ", ", dialect);
+                buf.push(')');
+            }
+            Expr::Function(f) => f.write_sql(buf, dialect),
+            Expr::Exists(select) => {
+                buf.push_str("EXISTS ");
+                write_subquery(buf, select, dialect);
+            }
+            Expr::InSelect(e, select) => {
+                write_operand(buf, e, 7, dialect);
+                buf.push_str(" IN ");
+                write_subquery(buf, select, dialect);
+            }
+            Expr::NotInSelect(e, select) => {
+                write_operand(buf, e, 7, dialect);
+                buf.push_str(" NOT IN ");
+                write_subquery(buf, select, dialect);
+            }
+            Expr::Subquery(select) => write_subquery(buf, select, dialect),
+            Expr::Literal(l) => l.write_sql(buf, dialect),
+            Expr::Cast(e, typ) => {
+                if dialect == Dialect::Postgres {
+                    write_operand(buf, e, u8::MAX, dialect);
+                    buf.push_str("::");
+                    buf.push_sql(typ, dialect);
+                } else {
+                    buf.push_str("CAST(");
+                    buf.push_sql(e.as_ref(), dialect);
+                    buf.push_str(" AS ");
+                    write_cast_type(buf, typ, dialect);
+                    buf.push(')');
+                }
+            }
+            Expr::Any(Operation::Eq, e, select) if dialect == Dialect::Sqlite => {
+                Expr::InSelect(e.clone(), select.clone()).write_sql(buf, dialect)
+            }
+            Expr::All(Operation::NotEq, e, select) if dialect == Dialect::Sqlite => {
+                Expr::NotInSelect(e.clone(), select.clone()).write_sql(buf, dialect)
+            }
+            Expr::Any(..) | Expr::All(..) if dialect == Dialect::Sqlite => {
+                panic!("Sqlite only supports = ANY and <> ALL subqueries")
+            }
+            Expr::Any(op, e, select) | Expr::All(op, e, select) => {
+                write_operand(buf, e, 6, dialect);
+                buf.push_sql(op, dialect);
+                if matches!(self, Expr::Any(..)) {
+                    buf.push_str("ANY ");
+                } else {
+                    buf.push_str("ALL ");
+                }
+                write_subquery(buf, select, dialect);
             }
         }
     }
 }
 
+/// Write `expr`, wrapped in parentheses if it binds less tightly than `min_precedence`.
+fn write_operand(buf: &mut String, expr: &Expr, min_precedence: u8, dialect: Dialect) {
+    if expr.precedence(dialect) < min_precedence {
+        buf.push('(');
+        expr.write_sql(buf, dialect);
+        buf.push(')');
+    } else {
+        expr.write_sql(buf, dialect);
+    }
+}
+
+/// Mysql and Sqlite only accept a limited set of type names in `CAST`.
+fn write_cast_type(buf: &mut String, typ: &Type, dialect: Dialect) {
+    use Type::*;
+    let s = match (dialect, typ) {
+        (Dialect::Mysql, Boolean | I16 | I32 | I64) => "SIGNED",
+        (Dialect::Mysql, F32) => "FLOAT",
+        (Dialect::Mysql, F64) => "DOUBLE",
+        (Dialect::Mysql, Decimal) => "DECIMAL",
+        (Dialect::Mysql, Numeric(p, s)) => return buf.push_str(&format!("DECIMAL({}, {})", p, s)),
+        (Dialect::Mysql, Bytes) => "BINARY",
+        (Dialect::Mysql, Date) => "DATE",
+        (Dialect::Mysql, Time) => "TIME",
+        (Dialect::Mysql, DateTime | NaiveDateTime) => "DATETIME",
+        (Dialect::Mysql, Json | Jsonb) => "JSON",
+        (Dialect::Mysql, Text | Uuid | Duration | Array(_)) => "CHAR",
+        (Dialect::Sqlite, Boolean | I16 | I32 | I64) => "INTEGER",
+        (Dialect::Sqlite, F32 | F64) => "REAL",
+        (Dialect::Sqlite, Decimal | Numeric(..)) => "NUMERIC",
+        (Dialect::Sqlite, Bytes) => "BLOB",
+        (Dialect::Sqlite, Other(_)) => return buf.push_sql(typ, dialect),
+        (Dialect::Sqlite, _) => "TEXT",
+        (_, typ) => return buf.push_sql(typ, dialect),
+    };
+    buf.push_str(s);
+}
+
+fn write_subquery(buf: &mut String, select: &Select, dialect: Dialect) {
+    buf.push('(');
+    select.write_sql(buf, dialect);
+    buf.push(')');
+}
+
 impl ToSql for Operation {
-    fn write_sql(&self, buf: &mut String, _dialect: Dialect) {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         match self {
             Operation::Eq => buf.push_str(" = "),
+            Operation::NotEq => buf.push_str(" <> "),
             Operation::Gte => buf.push_str(" >= "),
             Operation::Lte => buf.push_str(" <= "),
             Operation::Gt => buf.push_str(" > "),
             Operation::Lt => buf.push_str(" < "),
+            Operation::Like => buf.push_str(" LIKE "),
+            Operation::NotLike => buf.push_str(" NOT LIKE "),
+            Operation::ILike if dialect == Dialect::Postgres => buf.push_str(" ILIKE "),
+            Operation::ILike => buf.push_str(" LIKE "),
+            Operation::NotILike if dialect == Dialect::Postgres => buf.push_str(" NOT ILIKE "),
+            Operation::NotILike => buf.push_str(" NOT LIKE "),
+            Operation::Add => buf.push_str(" + "),
+            Operation::Sub => buf.push_str(" - "),
+            Operation::Mul => buf.push_str(" * "),
+            Operation::Div => buf.push_str(" / "),
+            Operation::Mod => buf.push_str(" % "),
+            Operation::Concat => buf.push_str(" || "),
         }
     }
 }
@@ -182,6 +580,7 @@ impl ToSql for Operation {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Where;
 
     #[test]
     fn test_and_clauses_wrapped_in_parentheses() {
@@ -193,4 +592,161 @@ mod tests {
         let sql = expr.to_sql(Dialect::Postgres);
         assert_eq!(sql, "((a = 1) AND (b = 2) AND (c = 3))");
     }
+
+    #[test]
+    fn test_precedence() {
+        let sum = Expr::binop(Operation::Add, Expr::column("a"), Expr::column("b"));
+        let expr = Expr::binop(Operation::Mul, sum.clone(), Expr::column("c"));
+        assert_eq!(expr.to_sql(Dialect::Postgres), r#"("a" + "b") * "c""#);
+
+        let expr = Expr::binop(Operation::Sub, Expr::column("a"), sum.clone());
+        assert_eq!(expr.to_sql(Dialect::Postgres), r#""a" - ("a" + "b")"#);
+
+        let expr = Expr::not(Expr::is_null(Expr::column("a")));
+        assert_eq!(expr.to_sql(Dialect::Postgres), r#"NOT "a" IS NULL"#);
+
+        let expr = Expr::is_null(Expr::not(Expr::column("a")));
+        assert_eq!(expr.to_sql(Dialect::Postgres), r#"(NOT "a") IS NULL"#);
+
+        let expr = Expr::new_eq(
+            Expr::column("a"),
+            Expr::like(Expr::column("b"), Expr::column("c")),
+        );
+        assert_eq!(expr.to_sql(Dialect::Postgres), r#""a" = "b" LIKE "c""#);
+        assert_eq!(expr.to_sql(Dialect::Mysql), "`a` = (`b` LIKE `c`)");
+        let expr = Expr::new_eq(
+            Expr::in_list(Expr::column("a"), vec![Expr::literal(1)]),
+            Expr::literal(false),
+        );
+        assert_eq!(expr.to_sql(Dialect::Sqlite), r#"("a" IN (1)) = 0"#);
+
+        let expr = Expr::concat(sum.clone(), Expr::column("c"));
+        assert_eq!(expr.to_sql(Dialect::Postgres), r#""a" + "b" || "c""#);
+        assert_eq!(expr.to_sql(Dialect::Sqlite), r#"("a" + "b") || "c""#);
+        let expr = Expr::binop(
+            Operation::Mul,
+            Expr::concat(Expr::column("a"), Expr::column("b")),
+            Expr::column("c"),
+        );
+        assert_eq!(expr.to_sql(Dialect::Postgres), r#"("a" || "b") * "c""#);
+        assert_eq!(expr.to_sql(Dialect::Sqlite), r#""a" || "b" * "c""#);
+
+        let expr = Expr::Neg(Box::new(Expr::literal(-5)));
+        assert_eq!(expr.to_sql(Dialect::Postgres), "- -5");
+    }
+
+    #[test]
+    fn test_in_between() {
+        let expr = Expr::new_or(vec![
+            Expr::in_list(Expr::column("a"), vec![Expr::param(1), Expr::param(2)]),
+            Expr::not_between(Expr::column("b"), Expr::param(3), Expr::param(4)),
+        ]);
+        assert_eq!(
+            expr.to_sql(Dialect::Postgres),
+            r#"(("a" IN ($1, $2)) OR ("b" NOT BETWEEN $3 AND $4))"#
+        );
+        assert_eq!(
+            Expr::in_list(Expr::column("a"), vec![]).to_sql(Dialect::Postgres),
+            "FALSE"
+        );
+    }
+
+    #[test]
+    fn test_dialect_operators() {
+        let expr = Expr::ilike(Expr::column("name"), Expr::param("%kurt%"));
+        assert_eq!(expr.to_sql(Dialect::Postgres), r#""name" ILIKE $1"#);
+        assert_eq!(expr.to_sql(Dialect::Mysql), "LOWER(`name`) LIKE LOWER(?)");
+
+        let expr = Expr::like(
+            Expr::concat(Expr::column("first"), Expr::column("last")),
+            Expr::param("k%"),
+        );
+        assert_eq!(expr.to_sql(Dialect::Sqlite), r#""first" || "last" LIKE ?"#);
+        assert_eq!(
+            expr.to_sql(Dialect::Mysql),
+            "CONCAT(`first`, `last`) LIKE ?"
+        );
+    }
+
+    #[test]
+    fn test_subqueries() {
+        let memberships = Select::default()
+            .select_raw("1")
+            .from("memberships")
+            .where_(Where::Expr(Expr::new_eq(
+                Expr::table_column("memberships", "team_id"),
+                Expr::table_column("posts", "team_id"),
+            )));
+        let expr = Expr::new_or(vec![
+            Expr::exists(memberships),
+            Expr::in_select(
+                Expr::column("author_id"),
+                Select::default().select_raw("id").from("admins"),
+            ),
+        ]);
+        assert_eq!(
+            expr.to_sql(Dialect::Postgres),
+            r#"((EXISTS (SELECT 1 FROM "memberships" WHERE "memberships"."team_id" = "posts"."team_id")) OR ("author_id" IN (SELECT id FROM "admins")))"#
+        );
+
+        let ids = Select::default().select_raw("id").from("admins");
+        let expr = Expr::any(Operation::Eq, Expr::column("author_id"), ids);
+        assert_eq!(
+            expr.to_sql(Dialect::Postgres),
+            r#""author_id" = ANY (SELECT id FROM "admins")"#
+        );
+        assert_eq!(
+            expr.to_sql(Dialect::Sqlite),
+            r#""author_id" IN (SELECT id FROM "admins")"#
+        );
+
+        let max = Select::default().select_raw("max(amount)").from("orders");
+        let expr = Expr::binop(Operation::Lt, Expr::column("amount"), Expr::subquery(max));
+        assert_eq!(
+            expr.to_sql(Dialect::Mysql),
+            "`amount` < (SELECT max(amount) FROM `orders`)"
+        );
+    }
+
+    #[test]
+    fn test_cast() {
+        let expr = Expr::cast(
+            Literal::string("c0ffee00-0000-0000-0000-000000000000"),
+            Type::Uuid,
+        );
+        assert_eq!(
+            expr.to_sql(Dialect::Postgres),
+            "'c0ffee00-0000-0000-0000-000000000000'::uuid"
+        );
+        let expr = Expr::cast(
+            Expr::binop(Operation::Add, Expr::column("a"), Expr::literal(1)),
+            Type::I64,
+        );
+        assert_eq!(expr.to_sql(Dialect::Postgres), r#"("a" + 1)::bigint"#);
+        assert_eq!(expr.to_sql(Dialect::Mysql), "CAST(`a` + 1 AS SIGNED)");
+        assert_eq!(expr.to_sql(Dialect::Sqlite), r#"CAST("a" + 1 AS INTEGER)"#);
+    }
+
+    #[test]
+    fn test_params_numbered_in_order() {
+        let expr = Expr::new_and(vec![
+            Expr::new_eq(Expr::column("a"), Expr::param(1)),
+            Expr::new_eq(Expr::column("b"), Expr::param("x")),
+        ]);
+        let (sql, params) = expr.to_sql_with_params(Dialect::Postgres);
+        assert_eq!(sql, r#"(("a" = $1) AND ("b" = $2))"#);
+        assert_eq!(params, vec![Param::Int(1), Param::Text("x".to_string())]);
+
+        let (sql, _) = expr.to_sql_with_params(Dialect::Sqlite);
+        assert_eq!(sql, r#"(("a" = ?) AND ("b" = ?))"#);
+
+        assert_eq!(Param::Float(f64::NAN), Param::Float(f64::NAN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Parameters can only be rendered by to_sql or to_sql_with_params")]
+    fn test_params_without_collector() {
+        let mut buf = String::new();
+        Expr::param(1).write_sql(&mut buf, Dialect::Postgres);
+    }
 }
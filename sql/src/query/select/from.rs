@@ -0,0 +1,270 @@
+use crate::query::{Compound, Function, Value};
+use crate::util::SqlExtension;
+use crate::{Dialect, Select, ToSql};
+
+/// A row source in the FROM clause.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum From {
+    Table {
+        schema: Option<String>,
+        table: String,
+        alias: Option<String>,
+    },
+    Select {
+        query: Box<Select>,
+        alias: String,
+    },
+    Compound {
+        query: Box<Compound>,
+        alias: String,
+    },
+    /// A table function, e.g. `unnest(...)` or `generate_series(...)`.
+    Function {
+        function: Function,
+        alias: Option<String>,
+        columns: Vec<String>,
+    },
+    /// A `VALUES` list.
+    Values {
+        rows: Vec<Value>,
+        alias: String,
+        columns: Vec<String>,
+    },
+}
+
+impl<T: Into<String>> std::convert::From<T> for From {
+    fn from(table: T) -> Self {
+        Self::Table {
+            schema: None,
+            table: table.into(),
+            alias: None,
+        }
+    }
+}
+
+impl From {
+    pub fn table(table: &str) -> Self {
+        table.into()
+    }
+
+    pub fn select(query: Select, alias: &str) -> Self {
+        Self::Select {
+            query: Box::new(query),
+            alias: alias.to_string(),
+        }
+    }
+
+    pub fn compound(query: Compound, alias: &str) -> Self {
+        Self::Compound {
+            query: Box::new(query),
+            alias: alias.to_string(),
+        }
+    }
+
+    pub fn function(function: Function, alias: &str) -> Self {
+        Self::Function {
+            function,
+            alias: Some(alias.to_string()),
+            columns: vec![],
+        }
+    }
+
+    pub fn values(rows: Vec<Value>, alias: &str) -> Self {
+        Self::Values {
+            rows,
+            alias: alias.to_string(),
+            columns: vec![],
+        }
+    }
+
+    pub fn schema(mut self, schema: &str) -> Self {
+        match &mut self {
+            Self::Table { schema: s, .. } => *s = Some(schema.to_string()),
+            _ => panic!("schema is only valid for tables"),
+        }
+        self
+    }
+
+    pub fn alias(mut self, alias: &str) -> Self {
+        match &mut self {
+            Self::Table { alias: a, .. } | Self::Function { alias: a, .. } => {
+                *a = Some(alias.to_string())
+            }
+            Self::Select { alias: a, .. }
+            | Self::Compound { alias: a, .. }
+            | Self::Values { alias: a, .. } => *a = alias.to_string(),
+        }
+        self
+    }
+
+    /// Name the columns of a table function or `VALUES` list, e.g. `AS v ("id", "name")`.
+    pub fn columns(mut self, names: &[&str]) -> Self {
+        match &mut self {
+            Self::Function { columns, .. } | Self::Values { columns, .. } => {
+                *columns = names.iter().map(|c| c.to_string()).collect();
+            }
+            _ => panic!("columns is only valid for table functions and VALUES lists"),
+        }
+        self
+    }
+}
+
+impl ToSql for From {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        match self {
+            From::Table {
+                schema,
+                table,
+                alias,
+            } => {
+                buf.push_table_name(schema, table, dialect);
+                write_alias(buf, alias.as_deref(), &[], dialect);
+            }
+            From::Select { query, alias } => {
+                buf.push('(');
+                buf.push_sql(query.as_ref(), dialect);
+                buf.push(')');
+                write_alias(buf, Some(alias), &[], dialect);
+            }
+            From::Compound { query, alias } => {
+                buf.push('(');
+                buf.push_sql(query.as_ref(), dialect);
+                buf.push(')');
+                write_alias(buf, Some(alias), &[], dialect);
+            }
+            From::Function {
+                function,
+                alias,
+                columns,
+            } => {
+                buf.push_sql(function, dialect);
+                write_alias(buf, alias.as_deref(), columns, dialect);
+            }
+            From::Values {
+                rows,
+                alias,
+                columns,
+            } => {
+                let columns = write_values(buf, rows, columns, dialect);
+                write_alias(buf, Some(alias), columns, dialect);
+            }
+        }
+    }
+}
+
+/// Write a parenthesized `VALUES` list, and return the column names still to be written
+/// with the alias.
+///
+/// Sqlite does not accept column names in an alias, so the columns are renamed in a subquery
+/// instead.
+pub(crate) fn write_values<'a>(
+    buf: &mut String,
+    rows: &[Value],
+    columns: &'a [String],
+    dialect: Dialect,
+) -> &'a [String] {
+    let rename = dialect == Dialect::Sqlite && !columns.is_empty();
+    buf.push('(');
+    if rename {
+        buf.push_str("SELECT ");
+        for (i, column) in columns.iter().enumerate() {
+            if i > 0 {
+                buf.push_str(", ");
+            }
+            buf.push_str("column");
+            buf.push_str(&(i + 1).to_string());
+            buf.push_str(" AS ");
+            buf.push_quoted(column, dialect);
+        }
+        buf.push_str(" FROM (");
+    }
+    buf.push_str("VALUES ");
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            buf.push_str(", ");
+        }
+        // Mysql requires row constructors in a VALUES statement.
+        if dialect == Dialect::Mysql {
+            buf.push_str("ROW");
+        }
+        buf.push('(');
+        buf.push_sql_sequence(&row.0, ", ", dialect);
+        buf.push(')');
+    }
+    buf.push(')');
+    if rename {
+        buf.push(')');
+        return &[];
+    }
+    columns
+}
+
+pub(crate) fn write_alias(
+    buf: &mut String,
+    alias: Option<&str>,
+    columns: &[String],
+    dialect: Dialect,
+) {
+    let Some(alias) = alias else {
+        if !columns.is_empty() {
+            panic!("Column names require an alias");
+        }
+        return;
+    };
+    buf.push_str(" AS ");
+    buf.push_quoted(alias, dialect);
+    if !columns.is_empty() {
+        if dialect == Dialect::Sqlite {
+            panic!("Sqlite does not support column names in a table alias");
+        }
+        buf.push_str(" (");
+        buf.push_quoted_sequence(columns, ", ", dialect);
+        buf.push(')');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::Expr;
+
+    #[test]
+    fn test_sources() {
+        let rows = vec![
+            Value::new().expr(Expr::param(1)).expr(Expr::param("a")),
+            Value::new().expr(Expr::param(2)).expr(Expr::param("b")),
+        ];
+        let from = From::values(rows, "v").columns(&["id", "name"]);
+        assert_eq!(
+            from.to_sql(Dialect::Postgres),
+            r#"(VALUES ($1, $2), ($3, $4)) AS "v" ("id", "name")"#
+        );
+        assert_eq!(
+            from.to_sql(Dialect::Mysql),
+            "(VALUES ROW(?, ?), ROW(?, ?)) AS `v` (`id`, `name`)"
+        );
+        assert_eq!(
+            from.to_sql(Dialect::Sqlite),
+            r#"(SELECT column1 AS "id", column2 AS "name" FROM (VALUES (?, ?), (?, ?))) AS "v""#
+        );
+
+        let series = Function::new("generate_series", vec![Expr::param(1), Expr::param(10)]);
+        let select = Select::default()
+            .select_raw("n")
+            .from(From::function(series, "s").columns(&["n"]));
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT n FROM generate_series($1, $2) AS "s" ("n")"#
+        );
+
+        let recent = Select::default().select_raw("*").from("orders").limit(10);
+        let select = Select::default()
+            .select_raw("count(*)")
+            .from(From::select(recent, "recent"));
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT count(*) FROM (SELECT * FROM "orders" LIMIT 10) AS "recent""#
+        );
+    }
+}
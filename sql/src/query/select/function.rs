@@ -0,0 +1,234 @@
+use crate::query::{Expr, OrderBy, Over};
+use crate::util::SqlExtension;
+use crate::{Dialect, ToSql};
+
+/// A function call, such as `COALESCE(a, b)` or an aggregate like `array_agg(x ORDER BY y)`.
+///
+/// A few portable names are rendered with the spelling of each dialect:
+/// `now`, `string_agg` (aka `group_concat`), `array_agg` and `random`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Function {
+    pub name: String,
+    pub args: Vec<Expr>,
+    pub distinct: bool,
+    /// Aggregate ORDER BY, e.g. `array_agg(x ORDER BY y)`
+    pub order: Vec<OrderBy>,
+    /// Aggregate filter, e.g. `count(*) FILTER (WHERE x)`. Emulated with `CASE` on Mysql.
+    pub filter: Option<Box<Expr>>,
+    /// Makes this a window function call, e.g. `row_number() OVER (...)`
+    pub over: Option<Box<Over>>,
+}
+
+impl Function {
+    pub fn new(name: &str, args: Vec<Expr>) -> Self {
+        Self {
+            name: name.to_string(),
+            args,
+            distinct: false,
+            order: vec![],
+            filter: None,
+            over: None,
+        }
+    }
+
+    pub fn count_star() -> Self {
+        Self::new("count", vec![Expr::Raw("*".to_string())])
+    }
+
+    pub fn count<E: Into<Expr>>(expr: E) -> Self {
+        Self::new("count", vec![expr.into()])
+    }
+
+    pub fn sum<E: Into<Expr>>(expr: E) -> Self {
+        Self::new("sum", vec![expr.into()])
+    }
+
+    pub fn min<E: Into<Expr>>(expr: E) -> Self {
+        Self::new("min", vec![expr.into()])
+    }
+
+    pub fn max<E: Into<Expr>>(expr: E) -> Self {
+        Self::new("max", vec![expr.into()])
+    }
+
+    pub fn avg<E: Into<Expr>>(expr: E) -> Self {
+        Self::new("avg", vec![expr.into()])
+    }
+
+    pub fn coalesce(args: Vec<Expr>) -> Self {
+        Self::new("coalesce", args)
+    }
+
+    pub fn now() -> Self {
+        Self::new("now", vec![])
+    }
+
+    pub fn array_agg<E: Into<Expr>>(expr: E) -> Self {
+        Self::new("array_agg", vec![expr.into()])
+    }
+
+    pub fn string_agg<E: Into<Expr>, S: Into<Expr>>(expr: E, separator: S) -> Self {
+        Self::new("string_agg", vec![expr.into(), separator.into()])
+    }
+
+    /// Distinguishes the subtotal rows of ROLLUP, CUBE and GROUPING SETS.
+    pub fn grouping(args: Vec<Expr>) -> Self {
+        Self::new("grouping", args)
+    }
+
+    pub fn row_number() -> Self {
+        Self::new("row_number", vec![])
+    }
+
+    pub fn rank() -> Self {
+        Self::new("rank", vec![])
+    }
+
+    pub fn dense_rank() -> Self {
+        Self::new("dense_rank", vec![])
+    }
+
+    pub fn lag<E: Into<Expr>>(expr: E) -> Self {
+        Self::new("lag", vec![expr.into()])
+    }
+
+    pub fn lead<E: Into<Expr>>(expr: E) -> Self {
+        Self::new("lead", vec![expr.into()])
+    }
+
+    pub fn distinct(mut self) -> Self {
+        self.distinct = true;
+        self
+    }
+
+    pub fn order_by(mut self, order: OrderBy) -> Self {
+        self.order.push(order);
+        self
+    }
+
+    pub fn filter<E: Into<Expr>>(mut self, filter: E) -> Self {
+        self.filter = Some(Box::new(filter.into()));
+        self
+    }
+
+    pub fn over(mut self, over: Over) -> Self {
+        self.over = Some(Box::new(over));
+        self
+    }
+
+    fn write_args(&self, buf: &mut String, dialect: Dialect) {
+        if self.distinct {
+            buf.push_str("DISTINCT ");
+        }
+        match &self.filter {
+            Some(filter) if dialect == Dialect::Mysql => {
+                for (i, arg) in self.args.iter().enumerate() {
+                    if i > 0 {
+                        buf.push_str(", ");
+                    }
+                    buf.push_str("CASE WHEN ");
+                    buf.push_sql(filter.as_ref(), dialect);
+                    buf.push_str(" THEN ");
+                    match arg {
+                        Expr::Raw(star) if star == "*" => buf.push('1'),
+                        arg => buf.push_sql(arg, dialect),
+                    }
+                    buf.push_str(" END");
+                }
+            }
+            _ => buf.push_sql_sequence(&self.args, ", ", dialect),
+        }
+        if !self.order.is_empty() {
+            buf.push_str(" ORDER BY ");
+            buf.push_sql_sequence(&self.order, ", ", dialect);
+        }
+    }
+}
+
+impl ToSql for Function {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        use Dialect::*;
+        match (self.name.to_lowercase().as_str(), dialect) {
+            ("now", Sqlite) => return buf.push_str("CURRENT_TIMESTAMP"),
+            ("now", _) => buf.push_str("now"),
+            ("random", Mysql) => buf.push_str("rand"),
+            ("string_agg" | "group_concat", Mysql) if self.args.len() == 2 => {
+                // Mysql takes the separator as a keyword argument, after any ORDER BY.
+                let mut f = self.clone();
+                let separator = f.args.pop().unwrap();
+                buf.push_str("group_concat(");
+                f.write_args(buf, dialect);
+                buf.push_str(" SEPARATOR ");
+                buf.push_sql(&separator, dialect);
+                buf.push(')');
+                return;
+            }
+            ("string_agg" | "group_concat", Postgres) => buf.push_str("string_agg"),
+            ("string_agg" | "group_concat", _) => buf.push_str("group_concat"),
+            ("array_agg", Mysql) => buf.push_str("json_arrayagg"),
+            ("array_agg", Sqlite) => buf.push_str("json_group_array"),
+            _ => buf.push_str(&self.name),
+        }
+        buf.push('(');
+        self.write_args(buf, dialect);
+        buf.push(')');
+        if let Some(filter) = &self.filter
+            && dialect != Mysql
+        {
+            buf.push_str(" FILTER (WHERE ");
+            buf.push_sql(filter.as_ref(), dialect);
+            buf.push(')');
+        }
+        if let Some(over) = &self.over {
+            buf.push(' ');
+            buf.push_sql(over.as_ref(), dialect);
+        }
+    }
+}
+
+impl From<Function> for Expr {
+    fn from(function: Function) -> Self {
+        Expr::Function(function)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate() {
+        let f = Function::array_agg(Expr::column("name"))
+            .distinct()
+            .order_by(OrderBy::new("name").desc());
+        assert_eq!(
+            f.to_sql(Dialect::Postgres),
+            r#"array_agg(DISTINCT "name" ORDER BY name DESC)"#
+        );
+
+        let f = Function::count_star().filter(Expr::is_not_null(Expr::column("deleted_at")));
+        assert_eq!(
+            f.to_sql(Dialect::Postgres),
+            r#"count(*) FILTER (WHERE "deleted_at" IS NOT NULL)"#
+        );
+        assert_eq!(
+            f.to_sql(Dialect::Mysql),
+            "count(CASE WHEN `deleted_at` IS NOT NULL THEN 1 END)"
+        );
+    }
+
+    #[test]
+    fn test_portable_names() {
+        let f = Function::string_agg(Expr::column("name"), Expr::Raw("', '".to_string()));
+        assert_eq!(f.to_sql(Dialect::Postgres), r#"string_agg("name", ', ')"#);
+        assert_eq!(
+            f.to_sql(Dialect::Mysql),
+            "group_concat(`name` SEPARATOR ', ')"
+        );
+        assert_eq!(f.to_sql(Dialect::Sqlite), r#"group_concat("name", ', ')"#);
+
+        assert_eq!(Function::now().to_sql(Dialect::Postgres), "now()");
+        assert_eq!(Function::now().to_sql(Dialect::Sqlite), "CURRENT_TIMESTAMP");
+    }
+}
@@ -1,34 +1,67 @@
-use crate::query::Where;
+use crate::query::select::from::{write_alias, write_values};
+use crate::query::{Compound, Function, Value, Where};
 use crate::util::SqlExtension;
 use crate::{Dialect, Select, ToSql};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::large_enum_variant)]
 pub enum JoinTable {
     Select(Select),
+    Compound(Compound),
     Table {
         schema: Option<String>,
         table: String,
     },
+    /// A table function, e.g. `unnest(...)`
+    Function(Function),
+    /// A `VALUES` list
+    Values(Vec<Value>),
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+impl std::convert::From<Select> for JoinTable {
+    fn from(select: Select) -> Self {
+        JoinTable::Select(select)
+    }
+}
+
+impl std::convert::From<Compound> for JoinTable {
+    fn from(compound: Compound) -> Self {
+        JoinTable::Compound(compound)
+    }
+}
+
+impl std::convert::From<Function> for JoinTable {
+    fn from(function: Function) -> Self {
+        JoinTable::Function(function)
+    }
+}
+
+impl std::convert::From<Vec<Value>> for JoinTable {
+    fn from(rows: Vec<Value>) -> Self {
+        JoinTable::Values(rows)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum JoinType {
+    #[default]
     Inner,
     Left,
     Right,
     Full,
-}
-
-impl Default for JoinType {
-    fn default() -> Self {
-        JoinType::Inner
-    }
+    /// Joins every row, without criteria.
+    Cross,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Criteria {
     On(Where),
     Using(Vec<String>),
+    /// Join on all columns with matching names, rendered as `NATURAL JOIN`.
+    Natural,
 }
 
 impl From<Where> for Criteria {
@@ -40,52 +73,94 @@ impl From<Where> for Criteria {
 impl ToSql for Criteria {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         match self {
+            Criteria::On(where_) if where_.is_empty() => buf.push_str(" ON TRUE"),
             Criteria::On(where_) => {
                 buf.push_str(" ON ");
                 buf.push_sql(where_, dialect);
             }
             Criteria::Using(columns) => {
                 buf.push_str(" USING (");
-                buf.push_quoted_sequence(columns, ", ");
+                buf.push_quoted_sequence(columns, ", ", dialect);
                 buf.push(')');
             }
+            Criteria::Natural => {}
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Join {
     pub typ: JoinType,
     pub table: JoinTable,
     pub alias: Option<String>,
+    /// Column names for the alias, e.g. `AS v ("id", "name")`
+    pub columns: Vec<String>,
     pub criteria: Criteria,
+    /// Not supported by Sqlite
+    pub lateral: bool,
 }
 
 impl Join {
     pub fn new(table: &str) -> Self {
-        Self {
-            typ: JoinType::Inner,
-            table: JoinTable::Table {
-                schema: None,
-                table: table.to_string(),
-            },
-            alias: None,
-            criteria: Criteria::On(Where::And(vec![])),
-        }
+        Self::with_type(JoinType::Inner, table)
     }
 
     pub fn left(table: &str) -> Self {
-        Self {
-            typ: JoinType::Left,
-            table: JoinTable::Table {
+        Self::with_type(JoinType::Left, table)
+    }
+
+    pub fn cross(table: &str) -> Self {
+        Self::with_type(JoinType::Cross, table)
+    }
+
+    pub fn with_type(typ: JoinType, table: &str) -> Self {
+        Self::source(
+            typ,
+            JoinTable::Table {
                 schema: None,
                 table: table.to_string(),
             },
+        )
+    }
+
+    /// Join a subquery, table function or `VALUES` list.
+    pub fn source(typ: JoinType, table: impl Into<JoinTable>) -> Self {
+        Self {
+            typ,
+            table: table.into(),
             alias: None,
+            columns: vec![],
             criteria: Criteria::On(Where::And(vec![])),
+            lateral: false,
         }
     }
 
+    pub fn columns(mut self, columns: &[&str]) -> Self {
+        self.columns = columns.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub fn lateral(mut self) -> Self {
+        self.lateral = true;
+        self
+    }
+
+    pub fn natural(mut self) -> Self {
+        self.criteria = Criteria::Natural;
+        self
+    }
+
+    pub fn on(mut self, on: Where) -> Self {
+        self.criteria = Criteria::On(on);
+        self
+    }
+
+    pub fn using(mut self, columns: &[&str]) -> Self {
+        self.criteria = Criteria::Using(columns.iter().map(|c| c.to_string()).collect());
+        self
+    }
+
     pub fn alias(mut self, alias: &str) -> Self {
         self.alias = Some(alias.to_string());
         self
@@ -101,33 +176,56 @@ impl ToSql for Join {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         use JoinTable::*;
         use JoinType::*;
+        if self.criteria == Criteria::Natural {
+            if self.typ == Cross {
+                panic!("A CROSS JOIN cannot be NATURAL");
+            }
+            buf.push_str("NATURAL ");
+        }
         match self.typ {
             Inner => buf.push_str("JOIN "),
             Left => buf.push_str("LEFT JOIN "),
             Right => buf.push_str("RIGHT JOIN "),
             Full => buf.push_str("FULL JOIN "),
+            Cross => buf.push_str("CROSS JOIN "),
         }
+        if self.lateral {
+            if dialect == Dialect::Sqlite {
+                panic!("Sqlite does not support LATERAL joins");
+            }
+            buf.push_str("LATERAL ");
+        }
+        let mut columns = self.columns.as_slice();
         match &self.table {
             Select(s) => {
                 buf.push('(');
                 buf.push_str(&s.to_sql(dialect));
                 buf.push(')');
             }
+            Compound(c) => {
+                buf.push('(');
+                buf.push_sql(c, dialect);
+                buf.push(')');
+            }
             Table { schema, table } => {
-                buf.push_table_name(schema, table);
+                buf.push_table_name(schema, table, dialect);
             }
+            Function(f) => buf.push_sql(f, dialect),
+            Values(rows) => columns = write_values(buf, rows, columns, dialect),
         }
-        if let Some(alias) = &self.alias {
-            buf.push_str(" AS ");
-            buf.push_quoted(alias);
+        write_alias(buf, self.alias.as_deref(), columns, dialect);
+        match (&self.criteria, self.typ) {
+            (Criteria::On(where_), Cross) if where_.is_empty() => {}
+            (_, Cross) => panic!("A CROSS JOIN cannot have criteria"),
+            (criteria, _) => buf.push_sql(criteria, dialect),
         }
-        buf.push_sql(&self.criteria, dialect);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::query::Expr;
 
     #[test]
     fn test_basic() {
@@ -138,7 +236,9 @@ mod tests {
                 table: "foo".to_string(),
             },
             alias: Some("bar".to_string()),
+            columns: vec![],
             criteria: Criteria::On(Where::raw("bar.id = parent.bar_id".to_string())),
+            lateral: false,
         };
         assert_eq!(
             j.to_sql(Dialect::Postgres),
@@ -156,4 +256,31 @@ mod tests {
             r#"JOIN "table" AS "bar" ON bar.id = parent.bar_id"#
         );
     }
+
+    #[test]
+    fn test_lateral() {
+        let latest = Select::default()
+            .select_raw("*")
+            .from("orders")
+            .where_raw("orders.user_id = u.id")
+            .order_desc("created_at")
+            .limit(1);
+        let j = Join::source(JoinType::Left, latest).lateral().alias("o");
+        assert_eq!(
+            j.to_sql(Dialect::Postgres),
+            r#"LEFT JOIN LATERAL (SELECT * FROM "orders" WHERE orders.user_id = u.id ORDER BY created_at DESC LIMIT 1) AS "o" ON TRUE"#
+        );
+
+        let tags = Function::new("unnest", vec![Expr::column("tags")]);
+        let j = Join::source(JoinType::Cross, tags)
+            .alias("t")
+            .columns(&["tag"]);
+        assert_eq!(
+            j.to_sql(Dialect::Postgres),
+            r#"CROSS JOIN unnest("tags") AS "t" ("tag")"#
+        );
+
+        let j = Join::left("profiles").natural();
+        assert_eq!(j.to_sql(Dialect::Mysql), "NATURAL LEFT JOIN `profiles`");
+    }
 }
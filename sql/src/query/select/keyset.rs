@@ -0,0 +1,365 @@
+use crate::query::{Direction, Expr, Literal, NullsOrder, Operation, OrderBy, Select, Where};
+use crate::{Param, Type};
+use anyhow::{Result, anyhow, bail};
+
+/// The position of a row in a keyset-paginated query: its values for the ORDER BY items.
+///
+/// Encode it to hand out as an opaque page token, and decode it when that page is requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cursor {
+    pub values: Vec<Literal>,
+}
+
+impl Cursor {
+    pub fn new(values: Vec<Literal>) -> Self {
+        Self { values }
+    }
+
+    /// Encode the cursor as a URL-safe string.
+    pub fn encode(&self) -> String {
+        let mut bytes = vec![];
+        for value in &self.values {
+            match value {
+                Literal::Null => bytes.push(b'n'),
+                Literal::Bool(true) => bytes.push(b't'),
+                Literal::Bool(false) => bytes.push(b'f'),
+                Literal::Int(i) => {
+                    bytes.push(b'i');
+                    bytes.extend(i.to_be_bytes());
+                }
+                Literal::Float(f) => {
+                    bytes.push(b'd');
+                    bytes.extend(f.to_be_bytes());
+                }
+                Literal::String(s) => push_bytes(&mut bytes, b's', s.as_bytes()),
+                Literal::Bytes(b) => push_bytes(&mut bytes, b'b', b),
+                Literal::Timestamp(s) => push_bytes(&mut bytes, b'T', s.as_bytes()),
+            }
+        }
+        base64_encode(&bytes)
+    }
+
+    /// Decode a cursor from `encode`. Cursors come from clients, so malformed input, including
+    /// NaN and infinite floats, is an error.
+    pub fn decode(s: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid cursor: {}", s);
+        let bytes = base64_decode(s).ok_or_else(invalid)?;
+        let mut rest = bytes.as_slice();
+        let mut values = vec![];
+        while let Some((&tag, tail)) = rest.split_first() {
+            rest = tail;
+            let value = match tag {
+                b'n' => Literal::Null,
+                b't' => Literal::Bool(true),
+                b'f' => Literal::Bool(false),
+                b'i' => Literal::Int(i64::from_be_bytes(take(&mut rest).ok_or_else(invalid)?)),
+                b'd' => {
+                    let f = f64::from_be_bytes(take(&mut rest).ok_or_else(invalid)?);
+                    if !f.is_finite() {
+                        return Err(invalid());
+                    }
+                    Literal::Float(f)
+                }
+                b's' | b'b' | b'T' => {
+                    let len = u32::from_be_bytes(take(&mut rest).ok_or_else(invalid)?);
+                    let (value, tail) = rest.split_at_checked(len as usize).ok_or_else(invalid)?;
+                    rest = tail;
+                    match tag {
+                        b'b' => Literal::Bytes(value.to_vec()),
+                        _ => {
+                            let s = String::from_utf8(value.to_vec()).map_err(|_| invalid())?;
+                            match tag {
+                                b's' => Literal::String(s),
+                                _ => Literal::Timestamp(s),
+                            }
+                        }
+                    }
+                }
+                _ => return Err(invalid()),
+            };
+            values.push(value);
+        }
+        Ok(Self { values })
+    }
+}
+
+fn push_bytes(bytes: &mut Vec<u8>, tag: u8, value: &[u8]) {
+    bytes.push(tag);
+    bytes.extend((value.len() as u32).to_be_bytes());
+    bytes.extend(value);
+}
+
+fn take<const N: usize>(rest: &mut &[u8]) -> Option<[u8; N]> {
+    let (value, tail) = rest.split_at_checked(N)?;
+    *rest = tail;
+    value.try_into().ok()
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Unpadded base64url
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    out
+}
+
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = vec![];
+    for chunk in s.as_bytes().chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            n |= (BASE64.iter().position(|b| b == c)? as u32) << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+impl Select {
+    /// Keyset pagination: only return the rows after `cursor`, in the order of `self.order`.
+    ///
+    /// The ORDER BY must identify rows uniquely, e.g. by ending with the primary key, and the
+    /// cursor must have a value for each item. If all items have the same direction and no
+    /// `NullsOrder`, the predicate is a row comparison, `(a, b) > (1, 2)`. Otherwise it is
+    /// expanded to `a > 1 OR (a = 1 AND b > 2)`.
+    ///
+    /// Set the `NullsOrder` of items on nullable columns, as the default position of NULLs
+    /// differs between dialects. Items without one are assumed to be NOT NULL.
+    ///
+    /// The cursor values are bound as parameters.
+    pub fn after(self, cursor: &Cursor) -> Result<Self> {
+        let predicate = keyset_predicate(&self.order, &cursor.values)?;
+        Ok(self.where_(predicate))
+    }
+
+    /// The cursor for the page after `rows`, the results of this query: the last row's values
+    /// for the ORDER BY items, read with `value(row, column)`.
+    ///
+    /// Returns `None` if there is no next page, i.e. there are fewer rows than the `LIMIT`, or
+    /// the query has no `LIMIT` and so returned every row.
+    pub fn next_cursor<R>(
+        &self,
+        rows: &[R],
+        mut value: impl FnMut(&R, &str) -> Literal,
+    ) -> Option<Cursor> {
+        if self.limit.is_none_or(|limit| rows.len() < limit) {
+            return None;
+        }
+        let last = rows.last()?;
+        Some(Cursor::new(
+            self.order.iter().map(|o| value(last, &o.column)).collect(),
+        ))
+    }
+}
+
+fn keyset_predicate(order: &[OrderBy], values: &[Literal]) -> Result<Where> {
+    if order.is_empty() {
+        bail!("Keyset pagination requires an ORDER BY");
+    }
+    if order.len() != values.len() {
+        bail!(
+            "Cursor has {} values, but the query is ordered by {} items",
+            values.len(),
+            order.len()
+        );
+    }
+    let descending = |o: &OrderBy| o.direction == Some(Direction::Desc);
+    let row_comparison = order.len() > 1
+        && order
+            .iter()
+            .all(|o| o.nulls.is_none() && descending(o) == descending(&order[0]))
+        && !values.contains(&Literal::Null);
+    if row_comparison {
+        let op = if descending(&order[0]) {
+            Operation::Lt
+        } else {
+            Operation::Gt
+        };
+        let columns = order.iter().map(|o| Expr::Raw(o.column.clone())).collect();
+        let values = values.iter().map(param).collect();
+        let predicate = Expr::binop(op, Expr::Row(columns), Expr::Row(values));
+        return Ok(Where::Expr(predicate));
+    }
+    let mut terms = vec![];
+    for (i, (o, value)) in order.iter().zip(values).enumerate() {
+        let Some(after) = after_value(o, value)? else {
+            continue;
+        };
+        let mut term: Vec<Where> = order[..i]
+            .iter()
+            .zip(values)
+            .map(|(o, value)| match value {
+                Literal::Null => Expr::is_null(Expr::Raw(o.column.clone())),
+                value => Expr::new_eq(Expr::Raw(o.column.clone()), param(value)),
+            })
+            .map(Where::Expr)
+            .collect();
+        term.push(after);
+        terms.push(match term.len() {
+            1 => term.pop().unwrap(),
+            _ => Where::And(term),
+        });
+    }
+    Ok(match terms.len() {
+        // The cursor is the last row.
+        0 => Where::Expr(Expr::literal(false)),
+        1 => terms.pop().unwrap(),
+        _ => Where::Or(terms),
+    })
+}
+
+/// The condition for a row to sort after `value` on a single ORDER BY item, if any can.
+fn after_value(order: &OrderBy, value: &Literal) -> Result<Option<Where>> {
+    let column = Expr::Raw(order.column.clone());
+    let op = match order.direction {
+        Some(Direction::Desc) => Operation::Lt,
+        _ => Operation::Gt,
+    };
+    Ok(match (value, order.nulls) {
+        (Literal::Null, None) => bail!(
+            "Cursor value for {} is NULL, but its ORDER BY item has no NullsOrder",
+            order.column
+        ),
+        (Literal::Null, Some(NullsOrder::First)) => Some(Where::Expr(Expr::is_not_null(column))),
+        (Literal::Null, Some(NullsOrder::Last)) => None,
+        (value, Some(NullsOrder::Last)) => Some(Where::Or(vec![
+            Where::Expr(Expr::binop(op, column.clone(), param(value))),
+            Where::Expr(Expr::is_null(column)),
+        ])),
+        (value, _) => Some(Where::Expr(Expr::binop(op, column, param(value)))),
+    })
+}
+
+/// A cursor value as a bound parameter. Timestamps are bound as text, and cast as their literal is.
+fn param(value: &Literal) -> Expr {
+    Expr::Param(match value {
+        Literal::Null => Param::Null,
+        Literal::Bool(b) => Param::Bool(*b),
+        Literal::Int(i) => Param::Int(*i),
+        Literal::Float(f) => Param::Float(*f),
+        Literal::String(s) => Param::Text(s.clone()),
+        Literal::Bytes(b) => Param::Bytes(b.clone()),
+        Literal::Timestamp(s) => {
+            return Expr::cast(Expr::Param(Param::Text(s.clone())), Type::DateTime);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dialect, ToSql};
+
+    #[test]
+    fn test_row_comparison() {
+        let select = Select::default()
+            .select_raw("*")
+            .from("posts")
+            .order_by(OrderBy::new("created_at").desc())
+            .order_by(OrderBy::new("id").desc())
+            .limit(20);
+        let cursor = Cursor::new(vec![Literal::timestamp("2024-01-31T12:00:00Z"), 42.into()]);
+        let select = select.after(&cursor).unwrap();
+        let (sql, params) = select.to_sql_with_params(Dialect::Postgres);
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "posts" WHERE (created_at, id) < ($1::timestamptz, $2) ORDER BY created_at DESC, id DESC LIMIT 20"#
+        );
+        assert_eq!(
+            params,
+            vec![Param::Text("2024-01-31T12:00:00Z".to_string()), Param::Int(42)]
+        );
+        assert_eq!(
+            select.to_sql(Dialect::Mysql),
+            "SELECT * FROM `posts` WHERE (created_at, id) < (CAST(? AS DATETIME), ?) ORDER BY created_at DESC, id DESC LIMIT 20"
+        );
+    }
+
+    #[test]
+    fn test_next_cursor() {
+        let select = Select::default()
+            .select_raw("*")
+            .from("posts")
+            .order_by(OrderBy::new("score").desc())
+            .order_by(OrderBy::new("id"))
+            .limit(2);
+        let rows = [(10, 1), (8, 3)];
+        let value = |row: &(i64, i64), column: &str| match column {
+            "score" => row.0.into(),
+            _ => row.1.into(),
+        };
+        let cursor = select.next_cursor(&rows, value).unwrap();
+        assert_eq!(cursor, Cursor::new(vec![8.into(), 3.into()]));
+        assert_eq!(
+            select.clone().after(&cursor).unwrap().where_.to_sql(Dialect::Postgres),
+            "(score < $1 OR score = $2 AND id > $3)"
+        );
+        assert_eq!(select.next_cursor(&rows[..1], value), None);
+        assert_eq!(select.next_cursor(&rows[..0], value), None);
+        let mut unlimited = select;
+        unlimited.limit = None;
+        assert_eq!(unlimited.next_cursor(&rows, value), None);
+    }
+
+    #[test]
+    fn test_expanded() {
+        let select = Select::default()
+            .select_raw("*")
+            .from("users")
+            .order_by(OrderBy::new("name").nulls_last())
+            .order_by(OrderBy::new("id").desc());
+        let after = select
+            .clone()
+            .after(&Cursor::new(vec!["kurt".into(), 7.into()]))
+            .unwrap();
+        assert_eq!(
+            after.where_.to_sql(Dialect::Postgres),
+            "((name > $1 OR name IS NULL) OR name = $2 AND id < $3)"
+        );
+        let after = select
+            .after(&Cursor::new(vec![Literal::Null, 7.into()]))
+            .unwrap();
+        assert_eq!(
+            after.where_.to_sql(Dialect::Postgres),
+            "name IS NULL AND id < $1"
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let cursor = Cursor::new(vec![
+            Literal::Null,
+            true.into(),
+            (-5).into(),
+            1.5.into(),
+            "it's ✓".into(),
+            vec![0u8, 255].into(),
+            Literal::timestamp("2024-01-31T12:00:00Z"),
+        ]);
+        let encoded = cursor.encode();
+        assert!(encoded.bytes().all(|b| BASE64.contains(&b)));
+        assert_eq!(Cursor::decode(&encoded).unwrap(), cursor);
+        assert!(Cursor::decode("not a cursor").is_err());
+        assert!(Cursor::decode(&encoded[..encoded.len() - 2]).is_err());
+
+        let nan = Cursor::new(vec![f64::NAN.into()]).encode();
+        assert!(Cursor::decode(&nan).is_err());
+        let infinity = Cursor::new(vec![f64::INFINITY.into()]).encode();
+        assert!(Cursor::decode(&infinity).is_err());
+    }
+}
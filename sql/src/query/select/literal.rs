@@ -0,0 +1,222 @@
+use crate::query::Expr;
+use crate::{Dialect, ToSql};
+
+/// A literal value, escaped for the target dialect when rendered.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Literal {
+    Null,
+    /// Rendered as `TRUE`/`FALSE`, or `1`/`0` on Sqlite.
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    /// Rendered as `E'\\x..'::bytea` on Postgres, which reads the same regardless of
+    /// `standard_conforming_strings`, and `X'..'` on Mysql and Sqlite.
+    Bytes(Vec<u8>),
+    /// An ISO 8601 timestamp, e.g. `2024-01-31T12:00:00Z`
+    Timestamp(String),
+}
+
+// Floats are compared by bits, so that NaN equals itself and `Eq` holds.
+impl PartialEq for Literal {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Literal::Null, Literal::Null) => true,
+            (Literal::Bool(a), Literal::Bool(b)) => a == b,
+            (Literal::Int(a), Literal::Int(b)) => a == b,
+            (Literal::Float(a), Literal::Float(b)) => a.to_bits() == b.to_bits(),
+            (Literal::String(a), Literal::String(b)) => a == b,
+            (Literal::Bytes(a), Literal::Bytes(b)) => a == b,
+            (Literal::Timestamp(a), Literal::Timestamp(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Literal {}
+
+impl Literal {
+    pub fn string(s: impl Into<String>) -> Self {
+        Literal::String(s.into())
+    }
+
+    pub fn timestamp(s: impl Into<String>) -> Self {
+        Literal::Timestamp(s.into())
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(value: bool) -> Self {
+        Literal::Bool(value)
+    }
+}
+
+impl From<i32> for Literal {
+    fn from(value: i32) -> Self {
+        Literal::Int(value as i64)
+    }
+}
+
+impl From<i64> for Literal {
+    fn from(value: i64) -> Self {
+        Literal::Int(value)
+    }
+}
+
+impl From<f64> for Literal {
+    fn from(value: f64) -> Self {
+        Literal::Float(value)
+    }
+}
+
+impl From<&str> for Literal {
+    fn from(value: &str) -> Self {
+        Literal::String(value.to_string())
+    }
+}
+
+impl From<String> for Literal {
+    fn from(value: String) -> Self {
+        Literal::String(value)
+    }
+}
+
+impl From<Vec<u8>> for Literal {
+    fn from(value: Vec<u8>) -> Self {
+        Literal::Bytes(value)
+    }
+}
+
+impl<T: Into<Literal>> From<Option<T>> for Literal {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Literal::Null)
+    }
+}
+
+impl From<Literal> for Expr {
+    fn from(literal: Literal) -> Self {
+        Expr::Literal(literal)
+    }
+}
+
+/// Write a quoted string literal.
+///
+/// Postgres strings containing backslashes are written as `E''` strings, so they are read the
+/// same way regardless of `standard_conforming_strings`. Mysql treats backslash as an escape
+/// character by default, so it is always escaped there.
+fn push_string(buf: &mut String, s: &str, dialect: Dialect) {
+    let backslash_escapes = match dialect {
+        Dialect::Postgres => s.contains('\\'),
+        Dialect::Mysql => true,
+        Dialect::Sqlite => false,
+    };
+    if backslash_escapes && dialect == Dialect::Postgres {
+        buf.push('E');
+    }
+    buf.push('\'');
+    for c in s.chars() {
+        match c {
+            '\'' => buf.push_str("''"),
+            '\\' if backslash_escapes => buf.push_str("\\\\"),
+            '\0' if dialect == Dialect::Mysql => buf.push_str("\\0"),
+            c => buf.push(c),
+        }
+    }
+    buf.push('\'');
+}
+
+impl ToSql for Literal {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        use Dialect::*;
+        match self {
+            Literal::Null => buf.push_str("NULL"),
+            Literal::Bool(b) => match (dialect, b) {
+                (Sqlite, true) => buf.push('1'),
+                (Sqlite, false) => buf.push('0'),
+                (_, true) => buf.push_str("TRUE"),
+                (_, false) => buf.push_str("FALSE"),
+            },
+            Literal::Int(i) => buf.push_str(&i.to_string()),
+            Literal::Float(f) if f.is_finite() => buf.push_str(&format!("{:?}", f)),
+            Literal::Float(f) => {
+                if dialect != Postgres {
+                    panic!("{:?} does not support NaN or infinite floats", dialect);
+                }
+                if f.is_nan() {
+                    buf.push_str("'NaN'::double precision");
+                } else if f.is_sign_positive() {
+                    buf.push_str("'Infinity'::double precision");
+                } else {
+                    buf.push_str("'-Infinity'::double precision");
+                }
+            }
+            Literal::String(s) => push_string(buf, s, dialect),
+            Literal::Bytes(bytes) => {
+                let hex = bytes
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>();
+                match dialect {
+                    Postgres => {
+                        buf.push_str("E'\\\\x");
+                        buf.push_str(&hex);
+                        buf.push_str("'::bytea");
+                    }
+                    Mysql | Sqlite => {
+                        buf.push_str("X'");
+                        buf.push_str(&hex);
+                        buf.push('\'');
+                    }
+                }
+            }
+            Literal::Timestamp(s) => match dialect {
+                Postgres => {
+                    push_string(buf, s, dialect);
+                    buf.push_str("::timestamptz");
+                }
+                Mysql => {
+                    buf.push_str("TIMESTAMP ");
+                    push_string(buf, s, dialect);
+                }
+                // Sqlite stores timestamps as ISO 8601 text.
+                Sqlite => push_string(buf, s, dialect),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_escaping() {
+        let l = Literal::string(r"it's a C:\path");
+        assert_eq!(l.to_sql(Dialect::Postgres), r"E'it''s a C:\\path'");
+        assert_eq!(l.to_sql(Dialect::Mysql), r"'it''s a C:\\path'");
+        assert_eq!(l.to_sql(Dialect::Sqlite), r"'it''s a C:\path'");
+
+        let l = Literal::string("it's");
+        assert_eq!(l.to_sql(Dialect::Postgres), "'it''s'");
+    }
+
+    #[test]
+    fn test_dialect_values() {
+        assert_eq!(Literal::Bool(true).to_sql(Dialect::Postgres), "TRUE");
+        assert_eq!(Literal::Bool(true).to_sql(Dialect::Sqlite), "1");
+        assert_eq!(Literal::Float(1.0).to_sql(Dialect::Postgres), "1.0");
+
+        let l = Literal::Bytes(vec![0xde, 0xad, 0x01]);
+        assert_eq!(l.to_sql(Dialect::Postgres), r"E'\\xdead01'::bytea");
+        assert_eq!(l.to_sql(Dialect::Mysql), "X'dead01'");
+
+        assert_eq!(Literal::Float(f64::NAN), Literal::Float(f64::NAN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Mysql does not support NaN or infinite floats")]
+    fn test_infinite_float() {
+        Literal::Float(f64::INFINITY).to_sql(Dialect::Mysql);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::util::SqlExtension;
+use crate::{Dialect, ToSql};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LockStrength {
+    #[default]
+    Update,
+    /// Only valid for Postgres
+    NoKeyUpdate,
+    Share,
+    /// Only valid for Postgres
+    KeyShare,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LockWait {
+    /// Block until the rows are available.
+    #[default]
+    Wait,
+    NoWait,
+    SkipLocked,
+}
+
+/// A row locking clause, e.g. `FOR UPDATE SKIP LOCKED`.
+///
+/// Sqlite has no row locks, so rendering a lock for Sqlite panics. Run the query in a
+/// `BEGIN IMMEDIATE` transaction instead.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lock {
+    pub strength: LockStrength,
+    /// Only lock rows from these tables (or aliases).
+    pub of: Vec<String>,
+    pub wait: LockWait,
+}
+
+impl Lock {
+    pub fn new(strength: LockStrength) -> Self {
+        Self {
+            strength,
+            ..Self::default()
+        }
+    }
+
+    pub fn update() -> Self {
+        Self::new(LockStrength::Update)
+    }
+
+    pub fn no_key_update() -> Self {
+        Self::new(LockStrength::NoKeyUpdate)
+    }
+
+    pub fn share() -> Self {
+        Self::new(LockStrength::Share)
+    }
+
+    pub fn key_share() -> Self {
+        Self::new(LockStrength::KeyShare)
+    }
+
+    pub fn of(mut self, table: &str) -> Self {
+        self.of.push(table.to_string());
+        self
+    }
+
+    pub fn nowait(mut self) -> Self {
+        self.wait = LockWait::NoWait;
+        self
+    }
+
+    pub fn skip_locked(mut self) -> Self {
+        self.wait = LockWait::SkipLocked;
+        self
+    }
+}
+
+impl ToSql for Lock {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        use LockStrength::*;
+        match (self.strength, dialect) {
+            (_, Dialect::Sqlite) => panic!(
+                "Sqlite does not support row locking; use a BEGIN IMMEDIATE transaction instead"
+            ),
+            (NoKeyUpdate | KeyShare, Dialect::Mysql) => {
+                panic!("Mysql does not support FOR NO KEY UPDATE or FOR KEY SHARE")
+            }
+            (Update, _) => buf.push_str("FOR UPDATE"),
+            (NoKeyUpdate, _) => buf.push_str("FOR NO KEY UPDATE"),
+            (Share, _) => buf.push_str("FOR SHARE"),
+            (KeyShare, _) => buf.push_str("FOR KEY SHARE"),
+        }
+        if !self.of.is_empty() {
+            buf.push_str(" OF ");
+            buf.push_quoted_sequence(&self.of, ", ", dialect);
+        }
+        match self.wait {
+            LockWait::Wait => {}
+            LockWait::NoWait => buf.push_str(" NOWAIT"),
+            LockWait::SkipLocked => buf.push_str(" SKIP LOCKED"),
+        }
+    }
+}
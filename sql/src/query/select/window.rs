@@ -0,0 +1,236 @@
+use crate::query::{Expr, OrderBy};
+use crate::util::SqlExtension;
+use crate::{Dialect, ToSql};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameUnit {
+    Rows,
+    Range,
+    /// Not supported by Mysql
+    Groups,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding(Box<Expr>),
+    CurrentRow,
+    Following(Box<Expr>),
+    UnboundedFollowing,
+}
+
+impl FrameBound {
+    pub fn preceding<E: Into<Expr>>(offset: E) -> Self {
+        FrameBound::Preceding(Box::new(offset.into()))
+    }
+
+    pub fn following<E: Into<Expr>>(offset: E) -> Self {
+        FrameBound::Following(Box::new(offset.into()))
+    }
+}
+
+/// A window frame, e.g. `ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+    pub unit: FrameUnit,
+    pub start: FrameBound,
+    /// If `None`, the frame is rendered without `BETWEEN`, and ends at the current row.
+    pub end: Option<FrameBound>,
+}
+
+/// A window specification, as used in `OVER (...)` and `WINDOW w AS (...)`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Over {
+    /// Name of a window defined in the `WINDOW` clause, to base this one on.
+    pub window: Option<String>,
+    pub partition: Vec<Expr>,
+    pub order: Vec<OrderBy>,
+    pub frame: Option<Frame>,
+}
+
+impl Over {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn window(name: &str) -> Self {
+        Self {
+            window: Some(name.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn partition_by<E: Into<Expr>>(mut self, expr: E) -> Self {
+        self.partition.push(expr.into());
+        self
+    }
+
+    pub fn order_by(mut self, order: OrderBy) -> Self {
+        self.order.push(order);
+        self
+    }
+
+    pub fn frame(mut self, unit: FrameUnit, start: FrameBound, end: FrameBound) -> Self {
+        self.frame = Some(Frame {
+            unit,
+            start,
+            end: Some(end),
+        });
+        self
+    }
+
+    pub fn rows(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame(FrameUnit::Rows, start, end)
+    }
+
+    pub fn range(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame(FrameUnit::Range, start, end)
+    }
+
+    pub fn groups(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame(FrameUnit::Groups, start, end)
+    }
+
+    fn is_window_reference(&self) -> bool {
+        self.window.is_some()
+            && self.partition.is_empty()
+            && self.order.is_empty()
+            && self.frame.is_none()
+    }
+
+    /// Write the specification, without the surrounding parentheses.
+    fn write_spec(&self, buf: &mut String, dialect: Dialect) {
+        let mut sep = "";
+        if let Some(window) = &self.window {
+            buf.push_quoted(window, dialect);
+            sep = " ";
+        }
+        if !self.partition.is_empty() {
+            buf.push_str(sep);
+            buf.push_str("PARTITION BY ");
+            buf.push_sql_sequence(&self.partition, ", ", dialect);
+            sep = " ";
+        }
+        if !self.order.is_empty() {
+            buf.push_str(sep);
+            buf.push_str("ORDER BY ");
+            buf.push_sql_sequence(&self.order, ", ", dialect);
+            sep = " ";
+        }
+        if let Some(frame) = &self.frame {
+            buf.push_str(sep);
+            buf.push_sql(frame, dialect);
+        }
+    }
+}
+
+impl ToSql for FrameBound {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        match self {
+            FrameBound::UnboundedPreceding => buf.push_str("UNBOUNDED PRECEDING"),
+            FrameBound::Preceding(offset) => {
+                buf.push_sql(offset.as_ref(), dialect);
+                buf.push_str(" PRECEDING");
+            }
+            FrameBound::CurrentRow => buf.push_str("CURRENT ROW"),
+            FrameBound::Following(offset) => {
+                buf.push_sql(offset.as_ref(), dialect);
+                buf.push_str(" FOLLOWING");
+            }
+            FrameBound::UnboundedFollowing => buf.push_str("UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+impl ToSql for Frame {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        match self.unit {
+            FrameUnit::Rows => buf.push_str("ROWS "),
+            FrameUnit::Range => buf.push_str("RANGE "),
+            FrameUnit::Groups if dialect == Dialect::Mysql => {
+                panic!("Mysql does not support GROUPS window frames")
+            }
+            FrameUnit::Groups => buf.push_str("GROUPS "),
+        }
+        match &self.end {
+            Some(end) => {
+                buf.push_str("BETWEEN ");
+                buf.push_sql(&self.start, dialect);
+                buf.push_str(" AND ");
+                buf.push_sql(end, dialect);
+            }
+            None => buf.push_sql(&self.start, dialect),
+        }
+    }
+}
+
+impl ToSql for Over {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        buf.push_str("OVER ");
+        if self.is_window_reference() {
+            buf.push_quoted(self.window.as_ref().unwrap(), dialect);
+            return;
+        }
+        buf.push('(');
+        self.write_spec(buf, dialect);
+        buf.push(')');
+    }
+}
+
+/// A named window, defined in the `WINDOW` clause of a SELECT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Window {
+    pub name: String,
+    pub over: Over,
+}
+
+impl ToSql for Window {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        buf.push_quoted(&self.name, dialect);
+        buf.push_str(" AS (");
+        self.over.write_spec(buf, dialect);
+        buf.push(')');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::Function;
+
+    #[test]
+    fn test_over() {
+        let f = Function::row_number().over(
+            Over::new()
+                .partition_by(Expr::column("user_id"))
+                .order_by(OrderBy::new("created_at").desc()),
+        );
+        assert_eq!(
+            f.to_sql(Dialect::Postgres),
+            r#"row_number() OVER (PARTITION BY "user_id" ORDER BY created_at DESC)"#
+        );
+
+        let f = Function::sum(Expr::column("amount"))
+            .over(Over::window("w").rows(FrameBound::preceding("2"), FrameBound::CurrentRow));
+        assert_eq!(
+            f.to_sql(Dialect::Postgres),
+            r#"sum("amount") OVER ("w" ROWS BETWEEN 2 PRECEDING AND CURRENT ROW)"#
+        );
+
+        let f = Function::rank().over(Over::window("w"));
+        assert_eq!(f.to_sql(Dialect::Postgres), r#"rank() OVER "w""#);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mysql does not support GROUPS window frames")]
+    fn test_groups_mysql() {
+        Function::sum(Expr::column("amount"))
+            .over(Over::new().groups(FrameBound::UnboundedPreceding, FrameBound::CurrentRow))
+            .to_sql(Dialect::Mysql);
+    }
+}
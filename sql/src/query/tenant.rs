@@ -0,0 +1,439 @@
+use crate::query::{
+    Criteria, Cte, CteQuery, Delete, Expr, From, Insert, Join, JoinTable, JoinType, OrderBy,
+    Select, SelectColumn, SelectExpression, Update, Where,
+};
+use crate::visit_mut::{
+    VisitMut, walk_cte, walk_delete, walk_expr, walk_insert, walk_select, walk_select_column,
+    walk_update, walk_where,
+};
+use anyhow::{Result, bail};
+
+/// Row-level filtering of queries on tenant-scoped tables.
+///
+/// The filter adds `predicate` for every reference to a scoped table: in the FROM clause,
+/// joins, subqueries and CTEs. Unqualified columns of the predicate are qualified with the
+/// alias of the table, or its name. Tables are given by name, or as `schema.table`, and are
+/// matched case-insensitively, like unquoted names. A `schema.table` entry also matches
+/// unqualified references to the table, which may resolve to it through the search_path.
+///
+/// Filtering fails if a scoped table is referenced in a way the predicate can't be added to:
+/// on the preserved side of a RIGHT or FULL join, LEFT joined with `USING` or `NATURAL`, as
+/// the target of an INSERT, or in raw SQL, including ORDER BY items. Raw SQL may still use a
+/// scoped table's name to qualify a column, e.g. `users.id`.
+///
+/// ```
+/// use sql::{Dialect, Expr, Select, TenantFilter, ToSql};
+///
+/// let filter = TenantFilter::new(Expr::new_eq(Expr::column("tenant_id"), Expr::param(7)))
+///     .table("projects");
+/// let mut select = Select::parse("SELECT p.name FROM projects p", Dialect::Postgres).unwrap();
+/// filter.filter_select(&mut select).unwrap();
+/// assert_eq!(
+///     select.to_sql(Dialect::Postgres),
+///     r#"SELECT "p"."name" FROM "projects" AS "p" WHERE "p"."tenant_id" = $1"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantFilter {
+    pub tables: Vec<String>,
+    pub predicate: Expr,
+}
+
+impl TenantFilter {
+    pub fn new(predicate: impl Into<Expr>) -> Self {
+        Self {
+            tables: vec![],
+            predicate: predicate.into(),
+        }
+    }
+
+    pub fn table(mut self, table: &str) -> Self {
+        self.tables.push(table.to_string());
+        self
+    }
+
+    pub fn filter_select(&self, select: &mut Select) -> Result<()> {
+        self.run(|v| v.visit_select(select))
+    }
+
+    pub fn filter_update(&self, update: &mut Update) -> Result<()> {
+        self.run(|v| v.visit_update(update))
+    }
+
+    pub fn filter_delete(&self, delete: &mut Delete) -> Result<()> {
+        self.run(|v| v.visit_delete(delete))
+    }
+
+    fn run(&self, f: impl FnOnce(&mut Inject)) -> Result<()> {
+        let mut inject = Inject {
+            filter: self,
+            error: None,
+        };
+        f(&mut inject);
+        match inject.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// An unqualified reference may resolve to a schema-qualified table through the
+    /// search_path, so it is scoped too.
+    fn is_scoped(&self, schema: &Option<String>, table: &str) -> bool {
+        self.tables.iter().any(|t| match t.split_once('.') {
+            Some((s, t)) => {
+                schema.as_deref().is_none_or(|schema| schema.eq_ignore_ascii_case(s))
+                    && t.eq_ignore_ascii_case(table)
+            }
+            None => t.eq_ignore_ascii_case(table),
+        })
+    }
+
+    /// The predicate for a reference to a scoped table.
+    fn predicate(&self, schema: &Option<String>, table: &str, alias: &Option<String>) -> Where {
+        let mut qualify = match alias {
+            Some(alias) => Qualify(None, alias),
+            None => Qualify(schema.as_deref(), table),
+        };
+        let mut predicate = self.predicate.clone();
+        qualify.visit_expr(&mut predicate);
+        Where::Expr(predicate)
+    }
+
+    /// Raw SQL can't be rewritten, so it must not mention a scoped table, other than as the
+    /// qualifier of a column.
+    fn check_raw(&self, sql: &str) -> Result<()> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let mut words = vec![];
+        let mut start = None;
+        for (i, c) in sql.char_indices().chain([(sql.len(), ' ')]) {
+            if is_word(c) {
+                start.get_or_insert(i);
+            } else if let Some(start) = start.take()
+                && !sql[i..].trim_start().starts_with('.')
+            {
+                words.push(&sql[start..i]);
+            }
+        }
+        for word in words {
+            let scoped = self.tables.iter().find(|t| {
+                let name = t.rsplit('.').next().unwrap();
+                name.eq_ignore_ascii_case(word)
+            });
+            if let Some(table) = scoped {
+                bail!("Cannot filter scoped table `{}` in raw SQL: {}", table, sql);
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the predicate for the tables in a FROM list, joined by `joins`.
+    fn from(&self, from: &[&From], joins: &mut [Join], where_: &mut Where) -> Result<()> {
+        let outer = joins
+            .iter()
+            .find(|j| matches!(j.typ, JoinType::Right | JoinType::Full));
+        let mut predicates = vec![];
+        for from in from {
+            let From::Table {
+                schema,
+                table,
+                alias,
+            } = from
+            else {
+                continue;
+            };
+            if !self.is_scoped(schema, table) {
+                continue;
+            }
+            if let Some(join) = outer {
+                bail!(
+                    "Cannot filter scoped table `{}` in a query with a {:?} join",
+                    table,
+                    join.typ
+                );
+            }
+            predicates.push(self.predicate(schema, table, alias));
+        }
+        for join in joins {
+            let JoinTable::Table { schema, table } = &join.table else {
+                continue;
+            };
+            if !self.is_scoped(schema, table) {
+                continue;
+            }
+            let predicate = self.predicate(schema, table, &join.alias);
+            match (join.typ, &mut join.criteria) {
+                (JoinType::Cross, _) => predicates.push(predicate),
+                (JoinType::Right | JoinType::Full, _) => bail!(
+                    "Cannot filter scoped table `{}` in a {:?} join",
+                    table,
+                    join.typ
+                ),
+                (_, Criteria::On(on)) => push_predicates(on, vec![predicate]),
+                (JoinType::Inner, _) => predicates.push(predicate),
+                (JoinType::Left, _) => bail!(
+                    "Cannot filter scoped table `{}` in a Left join without ON criteria",
+                    table
+                ),
+            }
+        }
+        push_predicates(where_, predicates);
+        Ok(())
+    }
+}
+
+/// AND the predicates to a condition. Raw terms are grouped, as they may contain an OR.
+fn push_predicates(where_: &mut Where, predicates: Vec<Where>) {
+    if predicates.is_empty() {
+        return;
+    }
+    let mut terms = match std::mem::replace(where_, Where::And(vec![])) {
+        Where::And(terms) => terms,
+        term => vec![term],
+    };
+    for term in &mut terms {
+        group_raw(term);
+    }
+    terms.extend(predicates);
+    *where_ = Where::And(terms);
+}
+
+fn group_raw(term: &mut Where) {
+    match term {
+        Where::And(terms) => terms.iter_mut().for_each(group_raw),
+        Where::Expr(Expr::Raw(s)) => *s = format!("({})", s),
+        #[allow(deprecated)]
+        Where::Raw(s) => *s = format!("({})", s),
+        _ => {}
+    }
+}
+
+struct Inject<'a> {
+    filter: &'a TenantFilter,
+    error: Option<anyhow::Error>,
+}
+
+impl Inject<'_> {
+    fn check(&mut self, result: Result<()>) {
+        if let Err(e) = result {
+            self.error.get_or_insert(e);
+        }
+    }
+}
+
+impl VisitMut for Inject<'_> {
+    fn visit_select(&mut self, node: &mut Select) {
+        walk_select(self, node);
+        let from: Vec<&From> = node.from.iter().collect();
+        let result = self.filter.from(&from, &mut node.join, &mut node.where_);
+        self.check(result);
+    }
+
+    fn visit_insert(&mut self, node: &mut Insert) {
+        if self.filter.is_scoped(&node.schema, &node.table) {
+            let result = Err(anyhow::anyhow!(
+                "Cannot filter INSERT into scoped table `{}`",
+                node.table
+            ));
+            self.check(result);
+        }
+        walk_insert(self, node);
+    }
+
+    fn visit_update(&mut self, node: &mut Update) {
+        walk_update(self, node);
+        let target = From::Table {
+            schema: node.schema.clone(),
+            table: node.table.clone(),
+            alias: node.alias.clone(),
+        };
+        let from: Vec<&From> = std::iter::once(&target).chain(&node.from).collect();
+        let result = self.filter.from(&from, &mut node.join, &mut node.where_);
+        self.check(result);
+    }
+
+    fn visit_delete(&mut self, node: &mut Delete) {
+        walk_delete(self, node);
+        let target = From::Table {
+            schema: node.schema.clone(),
+            table: node.table.clone(),
+            alias: node.alias.clone(),
+        };
+        let from: Vec<&From> = std::iter::once(&target).chain(&node.using).collect();
+        let result = self.filter.from(&from, &mut node.join, &mut node.where_);
+        self.check(result);
+    }
+
+    fn visit_cte(&mut self, node: &mut Cte) {
+        if let CteQuery::Raw(sql) = &node.query {
+            let result = self.filter.check_raw(sql);
+            self.check(result);
+        }
+        walk_cte(self, node);
+    }
+
+    fn visit_select_column(&mut self, node: &mut SelectColumn) {
+        if let SelectExpression::Raw(sql) = &node.expression {
+            let result = self.filter.check_raw(sql);
+            self.check(result);
+        }
+        walk_select_column(self, node);
+    }
+
+    fn visit_where(&mut self, node: &mut Where) {
+        #[allow(deprecated)]
+        if let Where::Raw(sql) = node {
+            let result = self.filter.check_raw(sql);
+            self.check(result);
+        }
+        walk_where(self, node);
+    }
+
+    fn visit_expr(&mut self, node: &mut Expr) {
+        if let Expr::Raw(sql) = node {
+            let result = self.filter.check_raw(sql);
+            self.check(result);
+        }
+        walk_expr(self, node);
+    }
+
+    fn visit_order_by(&mut self, node: &mut OrderBy) {
+        let result = self.filter.check_raw(&node.column);
+        self.check(result);
+    }
+}
+
+/// Qualify the unqualified columns of the predicate.
+struct Qualify<'a>(Option<&'a str>, &'a str);
+
+impl VisitMut for Qualify<'_> {
+    fn visit_column_name(
+        &mut self,
+        schema: &mut Option<String>,
+        table: &mut Option<String>,
+        _column: &mut String,
+    ) {
+        if table.is_none() {
+            *schema = self.0.map(str::to_string);
+            *table = Some(self.1.to_string());
+        }
+    }
+
+    // Columns in subqueries belong to their own tables.
+    fn visit_select(&mut self, _node: &mut Select) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dialect, ToSql};
+
+    fn filter() -> TenantFilter {
+        TenantFilter::new(Expr::new_eq(Expr::column("tenant_id"), Expr::param(7)))
+            .table("users")
+            .table("app.projects")
+    }
+
+    #[test]
+    fn test_select() {
+        let sql = r#"
+WITH recent AS (SELECT user_id FROM app.projects WHERE created_at > now())
+SELECT u.name, (SELECT count(*) FROM users) AS total
+FROM users u
+LEFT JOIN app.projects ON app.projects.owner_id = u.id
+JOIN teams t ON t.id = u.team_id
+WHERE u.id IN (SELECT user_id FROM recent) OR u.admin"#;
+        let mut select = Select::parse(sql, Dialect::Postgres).unwrap();
+        filter().filter_select(&mut select).unwrap();
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"WITH "recent" AS (SELECT "user_id" FROM "app"."projects" WHERE "created_at" > now() AND "app"."projects"."tenant_id" = $1) SELECT "u"."name", (SELECT count(*) FROM "users" WHERE "users"."tenant_id" = $2) AS "total" FROM "users" AS "u" LEFT JOIN "app"."projects" ON "app"."projects"."owner_id" = "u"."id" AND "app"."projects"."tenant_id" = $3 JOIN "teams" AS "t" ON "t"."id" = "u"."team_id" WHERE ("u"."id" IN (SELECT "user_id" FROM "recent") OR "u"."admin") AND "u"."tenant_id" = $4"#
+        );
+
+        // `projects` may resolve to `app.projects` through the search_path.
+        let sql = "SELECT * FROM projects WHERE EXISTS (SELECT 1 FROM Projects p JOIN other.projects o ON o.id = p.id)";
+        let mut select = Select::parse(sql, Dialect::Postgres).unwrap();
+        filter().filter_select(&mut select).unwrap();
+        assert_eq!(
+            select.to_sql(Dialect::Postgres),
+            r#"SELECT * FROM "projects" WHERE EXISTS (SELECT 1 FROM "projects" AS "p" JOIN "other"."projects" AS "o" ON "o"."id" = "p"."id" WHERE "p"."tenant_id" = $1) AND "projects"."tenant_id" = $2"#
+        );
+    }
+
+    #[test]
+    fn test_update_delete() {
+        let mut update = Update::parse(
+            "UPDATE users SET name = 'a' FROM teams WHERE teams.id = users.team_id",
+            Dialect::Postgres,
+        )
+        .unwrap();
+        filter().filter_update(&mut update).unwrap();
+        assert_eq!(
+            update.to_sql(Dialect::Postgres),
+            r#"UPDATE "users" SET "name" = 'a' FROM "teams" WHERE "teams"."id" = "users"."team_id" AND "users"."tenant_id" = $1"#
+        );
+
+        let mut delete = Delete::parse("DELETE FROM users AS u", Dialect::Postgres).unwrap();
+        filter().filter_delete(&mut delete).unwrap();
+        assert_eq!(
+            delete.to_sql(Dialect::Postgres),
+            r#"DELETE FROM "users" AS "u" WHERE "u"."tenant_id" = $1"#
+        );
+    }
+
+    #[test]
+    fn test_unprotected() {
+        let cases = [
+            "SELECT * FROM teams RIGHT JOIN users ON users.team_id = teams.id",
+            "SELECT * FROM users FULL JOIN teams ON users.team_id = teams.id",
+            "SELECT * FROM teams LEFT JOIN users USING (team_id)",
+            "WITH x AS (INSERT INTO users (name) VALUES ('a') RETURNING id) SELECT * FROM x",
+        ];
+        for sql in cases {
+            let mut select = Select::parse(sql, Dialect::Postgres).unwrap();
+            assert!(filter().filter_select(&mut select).is_err(), "{}", sql);
+        }
+        let mut select = Select::default()
+            .select_raw("*")
+            .from("teams")
+            .where_raw("id IN (SELECT team_id FROM users)");
+        let err = filter().filter_select(&mut select).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot filter scoped table `users` in raw SQL: id IN (SELECT team_id FROM users)"
+        );
+    }
+
+    #[test]
+    fn test_order_by() {
+        let cases = [
+            "SELECT id FROM teams ORDER BY (SELECT max(secret) FROM users)",
+            "SELECT * FROM (SELECT id FROM teams UNION SELECT id FROM teams ORDER BY (SELECT max(secret) FROM users)) t",
+            "SELECT rank() OVER (ORDER BY (SELECT max(secret) FROM users)) FROM teams",
+        ];
+        for sql in cases {
+            let mut select = Select::parse(sql, Dialect::Postgres).unwrap();
+            assert!(filter().filter_select(&mut select).is_err(), "{}", sql);
+        }
+        let mut update = Update::parse(
+            "UPDATE teams SET name = 'a' ORDER BY (SELECT max(secret) FROM users) LIMIT 1",
+            Dialect::Mysql,
+        )
+        .unwrap();
+        assert!(filter().filter_update(&mut update).is_err());
+        let mut delete = Delete::parse(
+            "DELETE FROM teams ORDER BY (SELECT max(secret) FROM users) LIMIT 1",
+            Dialect::Mysql,
+        )
+        .unwrap();
+        assert!(filter().filter_delete(&mut delete).is_err());
+
+        // Qualifiers aren't references to the table, and names match regardless of case.
+        let mut select =
+            Select::parse("SELECT name FROM USERS ORDER BY USERS.name", Dialect::Mysql).unwrap();
+        filter().filter_select(&mut select).unwrap();
+        assert_eq!(
+            select.to_sql(Dialect::Mysql),
+            "SELECT `name` FROM `USERS` WHERE `USERS`.`tenant_id` = ? ORDER BY USERS.name"
+        );
+    }
+}
@@ -1,22 +1,23 @@
+use crate::query::Compound;
 use crate::{Dialect, Select, ToSql};
 
+#[deprecated(note = "Use Compound instead")]
 pub struct Union {
     pub all: bool,
     pub queries: Vec<Select>,
 }
 
+#[allow(deprecated)]
 impl ToSql for Union {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
-        let all = if self.all { "ALL " } else { "" };
-        if self.queries.is_empty() {
+        let mut queries = self.queries.iter().cloned();
+        let Some(first) = queries.next() else {
             return;
-        }
-        let first = self.queries.iter().next().unwrap();
-        first.write_sql(buf, dialect);
-        for q in self.queries.iter().skip(1) {
-            buf.push_str(" UNION ");
-            buf.push_str(all);
-            q.write_sql(buf, dialect);
-        }
+        };
+        let compound = queries.fold(Compound::new(first), |c, q| match self.all {
+            true => c.union_all(q),
+            false => c.union(q),
+        });
+        compound.write_sql(buf, dialect);
     }
-}
\ No newline at end of file
+}
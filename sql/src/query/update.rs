@@ -1,15 +1,57 @@
-use crate::query::Cte;
-use crate::query::Where;
+use crate::query::{Cte, CteQuery, Expr, From, Join, OrderBy, SelectColumn, Where};
+use crate::query::cte::write_ctes;
 use crate::util::SqlExtension;
 use crate::{Dialect, ToSql};
 
+/// A single assignment in the SET clause of an UPDATE.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Assignment {
+    /// `"column" = value`
+    Column(String, Expr),
+    /// `("a", "b") = value`, typically a subquery. Not supported by Mysql.
+    Row(Vec<String>, Expr),
+}
+
+impl ToSql for Assignment {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
+        match self {
+            Assignment::Column(column, value) => {
+                buf.push_quoted(column, dialect);
+                buf.push_str(" = ");
+                buf.push_sql(value, dialect);
+            }
+            Assignment::Row(columns, value) => {
+                if dialect == Dialect::Mysql {
+                    panic!("Mysql does not support row assignment in UPDATE");
+                }
+                buf.push('(');
+                buf.push_quoted_sequence(columns, ", ", dialect);
+                buf.push_str(") = ");
+                buf.push_sql(value, dialect);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Update {
     pub ctes: Vec<Cte>,
     pub schema: Option<String>,
     pub table: String,
-    pub values: Vec<(String, String)>,
+    pub alias: Option<String>,
+    pub values: Vec<Assignment>,
+    /// Tables joined into the update. Rendered as `FROM` on Postgres and Sqlite, and as
+    /// additional tables in the `UPDATE` list on Mysql.
+    pub from: Vec<From>,
+    pub join: Vec<Join>,
     pub where_: Where,
+    pub returning: Vec<SelectColumn>,
+    /// Only valid for Mysql and Sqlite
+    pub order: Vec<OrderBy>,
+    /// Only valid for Mysql and Sqlite
+    pub limit: Option<usize>,
 }
 
 impl Update {
@@ -18,13 +60,56 @@ impl Update {
             ctes: vec![],
             schema: None,
             table: table.to_string(),
+            alias: None,
             values: vec![],
+            from: vec![],
+            join: vec![],
             where_: Where::And(vec![]),
+            returning: vec![],
+            order: vec![],
+            limit: None,
         }
     }
 
-    pub fn set(mut self, column: &str, value: &str) -> Self {
-        self.values.push((column.to_string(), value.to_string()));
+    pub fn with(mut self, name: &str, query: impl Into<CteQuery>) -> Self {
+        self.ctes.push(Cte::new(name, query));
+        self
+    }
+
+    pub fn with_cte(mut self, cte: Cte) -> Self {
+        self.ctes.push(cte);
+        self
+    }
+
+    pub fn schema(mut self, schema: &str) -> Self {
+        self.schema = Some(schema.to_string());
+        self
+    }
+
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.alias = Some(alias.to_string());
+        self
+    }
+
+    pub fn set(mut self, column: &str, value: impl Into<Expr>) -> Self {
+        self.values
+            .push(Assignment::Column(column.to_string(), value.into()));
+        self
+    }
+
+    pub fn set_row(mut self, columns: &[&str], value: impl Into<Expr>) -> Self {
+        let columns = columns.iter().map(|c| c.to_string()).collect();
+        self.values.push(Assignment::Row(columns, value.into()));
+        self
+    }
+
+    pub fn from(mut self, table: impl Into<From>) -> Self {
+        self.from.push(table.into());
+        self
+    }
+
+    pub fn join(mut self, join: Join) -> Self {
+        self.join.push(join);
         self
     }
 
@@ -35,29 +120,163 @@ impl Update {
         }
         self
     }
+
+    pub fn where_raw(self, where_: impl Into<String>) -> Self {
+        self.where_(Where::raw(where_))
+    }
+
+    pub fn returning(mut self, returning: &[&str]) -> Self {
+        self.returning = returning.iter().map(|&r| SelectColumn::new(r)).collect();
+        self
+    }
+
+    pub fn returning_expr(mut self, expression: impl Into<Expr>, alias: &str) -> Self {
+        self.returning
+            .push(SelectColumn::expr(expression).alias(alias));
+        self
+    }
+
+    pub fn order_by(mut self, order: OrderBy) -> Self {
+        self.order.push(order);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn write_joins(&self, buf: &mut String, dialect: Dialect) {
+        for join in &self.join {
+            buf.push(' ');
+            buf.push_sql(join, dialect);
+        }
+    }
 }
 
 impl ToSql for Update {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
-        if !self.ctes.is_empty() {
-            buf.push_str("WITH ");
-            buf.push_sql_sequence(&self.ctes, ", ", dialect);
-            buf.push(' ');
-        }
+        use Dialect::*;
+        write_ctes(buf, &self.ctes, dialect);
         buf.push_str("UPDATE ");
-        buf.push_table_name(&self.schema, &self.table);
-        buf.push_str(" SET ");
-        for (i, (column, value)) in self.values.iter().enumerate() {
-            if i > 0 {
+        buf.push_table_name(&self.schema, &self.table, dialect);
+        if let Some(alias) = &self.alias {
+            buf.push_str(" AS ");
+            buf.push_quoted(alias, dialect);
+        }
+        if dialect == Mysql {
+            for from in &self.from {
                 buf.push_str(", ");
+                buf.push_sql(from, dialect);
+            }
+            self.write_joins(buf, dialect);
+        }
+        buf.push_str(" SET ");
+        buf.push_sql_sequence(&self.values, ", ", dialect);
+        if dialect != Mysql {
+            if self.from.is_empty() && !self.join.is_empty() {
+                panic!("{:?} does not support JOIN in UPDATE without a FROM table", dialect);
+            }
+            if !self.from.is_empty() {
+                buf.push_str(" FROM ");
+                buf.push_sql_sequence(&self.from, ", ", dialect);
             }
-            buf.push_quoted(column);
-            buf.push_str(" = ");
-            buf.push_str(value);
+            self.write_joins(buf, dialect);
         }
         if !self.where_.is_empty() {
             buf.push_str(" WHERE ");
             self.where_.write_sql(buf, dialect);
         }
+        if !self.returning.is_empty() {
+            if dialect == Mysql {
+                panic!("Mysql does not support RETURNING in UPDATE");
+            }
+            buf.push_str(" RETURNING ");
+            buf.push_sql_sequence(&self.returning, ", ", dialect);
+        }
+        if !self.order.is_empty() || self.limit.is_some() {
+            match dialect {
+                Postgres => panic!("Postgres does not support ORDER BY or LIMIT in UPDATE"),
+                Mysql if !self.from.is_empty() || !self.join.is_empty() => {
+                    panic!("Mysql does not support ORDER BY or LIMIT in multi-table UPDATE")
+                }
+                _ => {}
+            }
+        }
+        if !self.order.is_empty() {
+            buf.push_str(" ORDER BY ");
+            buf.push_sql_sequence(&self.order, ", ", dialect);
+        }
+        if let Some(limit) = self.limit {
+            buf.push_str(" LIMIT ");
+            buf.push_str(&limit.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{Literal, Select};
+
+    #[test]
+    fn test_from_returning() {
+        let update = Update::new("orders")
+            .alias("o")
+            .set("status", Literal::string("shipped"))
+            .set("shipped_at", Expr::Raw("now()".to_string()))
+            .from("shipments")
+            .where_raw("shipments.order_id = o.id")
+            .returning(&["id"]);
+        assert_eq!(
+            update.to_sql(Dialect::Postgres),
+            r#"UPDATE "orders" AS "o" SET "status" = 'shipped', "shipped_at" = now() FROM "shipments" WHERE shipments.order_id = o.id RETURNING "id""#
+        );
+    }
+
+    #[test]
+    fn test_mysql_join() {
+        let update = Update::new("orders")
+            .join(Join::new("shipments").on_raw("shipments.order_id = orders.id"))
+            .set("status", Literal::string("shipped"));
+        assert_eq!(
+            update.to_sql(Dialect::Mysql),
+            "UPDATE `orders` JOIN `shipments` ON shipments.order_id = orders.id SET `status` = 'shipped'"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Postgres does not support JOIN in UPDATE without a FROM table")]
+    fn test_join_without_from() {
+        Update::new("orders")
+            .join(Join::new("shipments").on_raw("shipments.order_id = orders.id"))
+            .set("status", Literal::string("shipped"))
+            .to_sql(Dialect::Postgres);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mysql does not support RETURNING in UPDATE")]
+    fn test_mysql_returning() {
+        Update::new("orders")
+            .set("status", Literal::string("shipped"))
+            .returning(&["id"])
+            .to_sql(Dialect::Mysql);
+    }
+
+    #[test]
+    fn test_row_assignment() {
+        let totals = Select::default()
+            .select_raw("count(*)")
+            .select_raw("sum(amount)")
+            .from("orders")
+            .where_raw("orders.user_id = users.id");
+        let update = Update::new("users")
+            .set_row(&["order_count", "order_total"], Expr::subquery(totals))
+            .order_by(OrderBy::new("id"))
+            .limit(100);
+        assert_eq!(
+            update.to_sql(Dialect::Sqlite),
+            r#"UPDATE "users" SET ("order_count", "order_total") = (SELECT count(*), sum(amount) FROM "orders" WHERE orders.user_id = users.id) ORDER BY id LIMIT 100"#
+        );
     }
 }
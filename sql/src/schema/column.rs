@@ -29,7 +29,7 @@ pub struct Column {
 
 impl ToSql for Column {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
-        buf.push_quoted(&self.name);
+        buf.push_quoted(&self.name, dialect);
         buf.push(' ');
         buf.push_str(&self.typ.to_sql(dialect));
         if let Some(generated) = &self.generated {
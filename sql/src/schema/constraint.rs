@@ -4,6 +4,11 @@ use crate::util::SqlExtension;
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ForeignKey {
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub schema: Option<String>,
     pub table: String,
     pub columns: Vec<String>,
 }
@@ -17,7 +22,11 @@ pub enum Constraint {
 
 impl Constraint {
     pub fn foreign_key(table: String, columns: Vec<String>) -> Self {
-        Constraint::ForeignKey(ForeignKey { table, columns })
+        Constraint::ForeignKey(ForeignKey {
+            schema: None,
+            table,
+            columns,
+        })
     }
 
     pub fn name(&self) -> &str {
@@ -28,12 +37,12 @@ impl Constraint {
 }
 
 impl ToSql for ForeignKey {
-    fn write_sql(&self, buf: &mut String, _dialect: Dialect) {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         buf.push_str("REFERENCES ");
-        buf.push_quoted(&self.table);
+        buf.push_table_name(&self.schema, &self.table, dialect);
         if !self.columns.is_empty() {
             buf.push('(');
-            buf.push_quoted_sequence(&self.columns, ", ");
+            buf.push_quoted_sequence(&self.columns, ", ", dialect);
             buf.push(')');
         }
     }
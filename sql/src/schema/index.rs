@@ -1,8 +1,10 @@
 use crate::{Dialect, ToSql};
 use crate::util::SqlExtension;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IndexKind {
+    #[default]
     BTree,
     Hash,
     Gist,
@@ -11,14 +13,9 @@ pub enum IndexKind {
     Other(String),
 }
 
-impl Default for IndexKind {
-    fn default() -> Self {
-        IndexKind::BTree
-    }
-}
-
 /// Create index action for a table
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Index {
     pub name: String,
     pub unique: bool,
@@ -29,26 +26,29 @@ pub struct Index {
 }
 
 impl ToSql for Index {
-    fn write_sql(&self, buf: &mut String, _: Dialect) {
+    fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         buf.push_str("CREATE ");
         if self.unique {
             buf.push_str("UNIQUE ");
         }
-        buf.push_quoted(&self.name);
+        buf.push_str("INDEX ");
+        buf.push_quoted(&self.name, dialect);
         buf.push_str(" ON ");
-        buf.push_table_name(&self.schema, &self.table);
-        buf.push_str(" USING ");
+        buf.push_table_name(&self.schema, &self.table, dialect);
         match &self.kind {
             // btree is default
             IndexKind::BTree => {}
-            IndexKind::Hash => buf.push_str("HASH"),
-            IndexKind::Gist => buf.push_str("GIST"),
-            IndexKind::SpGist => buf.push_str("SPGIST"),
-            IndexKind::Brin => buf.push_str("BRIN"),
-            IndexKind::Other(kind) => buf.push_str(kind),
+            IndexKind::Hash => buf.push_str(" USING HASH"),
+            IndexKind::Gist => buf.push_str(" USING GIST"),
+            IndexKind::SpGist => buf.push_str(" USING SPGIST"),
+            IndexKind::Brin => buf.push_str(" USING BRIN"),
+            IndexKind::Other(kind) => {
+                buf.push_str(" USING ");
+                buf.push_str(kind);
+            }
         }
         buf.push_str(" (");
-        buf.push_quoted_sequence(&self.columns, ", ");
+        buf.push_quoted_sequence(&self.columns, ", ", dialect);
         buf.push(')');
     }
 }
\ No newline at end of file
@@ -20,6 +20,10 @@ use anyhow::Result;
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Schema {
     pub tables: Vec<Table>,
+    /// Matched by name in `migrate_to`, which creates the indexes missing from the current
+    /// schema. Changed and removed indexes are not migrated.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub indexes: Vec<Index>,
 }
 
 impl Schema {
@@ -28,6 +32,12 @@ impl Schema {
         migrate(self, desired, options)
     }
 
+    /// Add the table and indexes of `T`.
+    pub fn add<T: SqlTable>(&mut self) {
+        self.tables.push(T::table());
+        self.indexes.extend(T::indexes());
+    }
+
     /// Propagate the schema name to all tables.
     pub fn name_schema(&mut self, schema: &str) {
         for table in &mut self.tables {
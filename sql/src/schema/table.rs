@@ -1,5 +1,6 @@
 use crate::{Dialect, ToSql};
 use crate::schema::column::Column;
+use crate::schema::index::Index;
 use crate::util::SqlExtension;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -11,6 +12,7 @@ pub struct Table {
 }
 
 impl Table {
+    /// The first primary key column. A composite primary key is set on each of its columns.
     pub fn primary_key(&self) -> Option<&Column> {
         self.columns.iter().find(|c| c.primary_key)
     }
@@ -34,12 +36,40 @@ impl Table {
     }
 }
 
+/// A Rust type that is stored in a table. Implement it with `#[derive(SqlTable)]` from
+/// `kurtbuilds_sql_derive`, then collect the tables into a `Schema` with `Schema::add`.
+pub trait SqlTable {
+    fn table() -> Table;
+
+    fn indexes() -> Vec<Index> {
+        vec![]
+    }
+}
+
 impl ToSql for Table {
     fn write_sql(&self, buf: &mut String, dialect: Dialect) {
         buf.push_str("CREATE TABLE IF NOT EXISTS ");
-        buf.push_table_name(&self.schema, &self.name);
+        buf.push_table_name(&self.schema, &self.name, dialect);
         buf.push_str(" (\n");
-        buf.push_sql_sequence(&self.columns, ",\n", dialect);
+        let primary_key: Vec<&Column> = self.columns.iter().filter(|c| c.primary_key).collect();
+        if primary_key.len() > 1 {
+            // A composite primary key is a table constraint.
+            let columns: Vec<Column> = self
+                .columns
+                .iter()
+                .map(|c| Column {
+                    primary_key: false,
+                    ..c.clone()
+                })
+                .collect();
+            buf.push_sql_sequence(&columns, ",\n", dialect);
+            buf.push_str(",\nPRIMARY KEY (");
+            let names: Vec<String> = primary_key.iter().map(|c| c.name.clone()).collect();
+            buf.push_quoted_sequence(&names, ", ", dialect);
+            buf.push(')');
+        } else {
+            buf.push_sql_sequence(&self.columns, ",\n", dialect);
+        }
         buf.push_str("\n)");
     }
 }
@@ -74,6 +74,16 @@ impl FromStr for Type {
             "character varying" => Text,
             "varchar" => Text,
             "integer" => I32,
+            "int" => I32,
+            "int4" => I32,
+            "int2" => I16,
+            "float8" => F64,
+            "float4" => F32,
+            "decimal" => Decimal,
+            "timestamptz" => DateTime,
+            "timestamp" => NaiveDateTime,
+            "time" => Time,
+            "time without time zone" => Time,
             "ARRAY" => panic!(
                 "Encountered `ARRAY` type when reading data schema from database. ARRAY must be handled separately."
             ),
@@ -1,3 +1,6 @@
+use crate::format::{FormatOptions, format_sql};
+use std::cell::RefCell;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Dialect {
     Postgres,
@@ -8,9 +11,159 @@ pub enum Dialect {
 pub trait ToSql {
     fn to_sql(&self, dialect: Dialect) -> String {
         let mut buf = String::new();
-        self.write_sql(&mut buf, dialect);
+        if PARAMS.with(|p| p.borrow().is_some()) {
+            self.write_sql(&mut buf, dialect);
+        } else {
+            collect_params(|| self.write_sql(&mut buf, dialect));
+        }
         buf
     }
 
+    /// Render the query, replacing every `Expr::Param` with a dialect-specific placeholder
+    /// (`$n` for Postgres, `?` for Mysql and Sqlite). Parameters are returned in the order
+    /// they appear in the generated SQL.
+    fn to_sql_with_params(&self, dialect: Dialect) -> (String, Vec<Param>) {
+        let mut buf = String::new();
+        let params = collect_params(|| self.write_sql(&mut buf, dialect));
+        (buf, params)
+    }
+
+    /// Render the query laid out according to `options`, e.g. one clause per line.
+    fn to_sql_formatted(&self, dialect: Dialect, options: &FormatOptions) -> String {
+        format_sql(&self.to_sql(dialect), dialect, options)
+    }
+
+    /// Like `to_sql_formatted`, but also returns the parameters, as `to_sql_with_params` does.
+    fn to_sql_formatted_with_params(
+        &self,
+        dialect: Dialect,
+        options: &FormatOptions,
+    ) -> (String, Vec<Param>) {
+        let (sql, params) = self.to_sql_with_params(dialect);
+        (format_sql(&sql, dialect, options), params)
+    }
+
     fn write_sql(&self, buf: &mut String, dialect: Dialect);
-}
\ No newline at end of file
+}
+
+/// A value bound to a query placeholder.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Param {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl PartialEq for Param {
+    fn eq(&self, other: &Self) -> bool {
+        use Param::*;
+        match (self, other) {
+            (Null, Null) => true,
+            (Bool(a), Bool(b)) => a == b,
+            (Int(a), Int(b)) => a == b,
+            // Floats are compared by bits, so that NaN equals itself and `Eq` holds.
+            (Float(a), Float(b)) => a.to_bits() == b.to_bits(),
+            (Text(a), Text(b)) => a == b,
+            (Bytes(a), Bytes(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Param {}
+
+impl From<bool> for Param {
+    fn from(value: bool) -> Self {
+        Param::Bool(value)
+    }
+}
+
+impl From<i32> for Param {
+    fn from(value: i32) -> Self {
+        Param::Int(value as i64)
+    }
+}
+
+impl From<i64> for Param {
+    fn from(value: i64) -> Self {
+        Param::Int(value)
+    }
+}
+
+impl From<f64> for Param {
+    fn from(value: f64) -> Self {
+        Param::Float(value)
+    }
+}
+
+impl From<&str> for Param {
+    fn from(value: &str) -> Self {
+        Param::Text(value.to_string())
+    }
+}
+
+impl From<String> for Param {
+    fn from(value: String) -> Self {
+        Param::Text(value)
+    }
+}
+
+impl From<Vec<u8>> for Param {
+    fn from(value: Vec<u8>) -> Self {
+        Param::Bytes(value)
+    }
+}
+
+impl<T: Into<Param>> From<Option<T>> for Param {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Param::Null)
+    }
+}
+
+thread_local! {
+    static PARAMS: RefCell<Option<Vec<Param>>> = const { RefCell::new(None) };
+}
+
+/// Restores the enclosing collector, even if rendering panics.
+struct ParamsGuard(Option<Vec<Param>>);
+
+impl Drop for ParamsGuard {
+    fn drop(&mut self) {
+        PARAMS.with(|p| *p.borrow_mut() = self.0.take());
+    }
+}
+
+fn collect_params(f: impl FnOnce()) -> Vec<Param> {
+    let outer = PARAMS.with(|p| p.borrow_mut().replace(Vec::new()));
+    let guard = ParamsGuard(outer);
+    f();
+    let params = PARAMS.with(|p| p.borrow_mut().take()).unwrap_or_default();
+    drop(guard);
+    params
+}
+
+/// Register a parameter with the active collector, and write its placeholder.
+///
+/// Panics when called outside of `to_sql` or `to_sql_with_params`, e.g. from `write_sql` called
+/// directly, as the placeholder couldn't be numbered.
+pub(crate) fn push_param(buf: &mut String, param: &Param, dialect: Dialect) {
+    let n = PARAMS.with(|p| {
+        let mut p = p.borrow_mut();
+        let params = p
+            .as_mut()
+            .expect("Parameters can only be rendered by to_sql or to_sql_with_params");
+        params.push(param.clone());
+        params.len()
+    });
+    match dialect {
+        Dialect::Postgres => {
+            buf.push('$');
+            buf.push_str(&n.to_string());
+        }
+        Dialect::Mysql | Dialect::Sqlite => buf.push('?'),
+    }
+}
@@ -1,29 +1,37 @@
 use crate::{Dialect, ToSql};
 
 pub(crate) trait SqlExtension {
-    fn push_quoted<T: AsRef<str>>(&mut self, s: T);
-    fn push_table_name(&mut self, schema: &Option<String>, table: &str);
+    fn push_quoted<T: AsRef<str>>(&mut self, s: T, dialect: Dialect);
+    fn push_table_name(&mut self, schema: &Option<String>, table: &str, dialect: Dialect);
     fn push_sql<T: ToSql>(&mut self, sql: &T, dialect: Dialect);
     fn push_sql_sequence<T: ToSql>(&mut self, sql: &[T], separator: &str, dialect: Dialect);
-    fn push_quoted_sequence(&mut self, sql: &[String], separator: &str);
+    fn push_quoted_sequence(&mut self, sql: &[String], separator: &str, dialect: Dialect);
 }
 
 impl SqlExtension for String {
-    fn push_quoted<T: AsRef<str>>(&mut self, s: T) {
-        if s.as_ref().contains('"') {
-            panic!("Cannot quote string with double quotes");
+    /// Quote an identifier, doubling any embedded quote characters.
+    /// Mysql uses backticks, which are accepted regardless of `ANSI_QUOTES`.
+    fn push_quoted<T: AsRef<str>>(&mut self, s: T, dialect: Dialect) {
+        let quote = match dialect {
+            Dialect::Postgres | Dialect::Sqlite => '"',
+            Dialect::Mysql => '`',
+        };
+        self.push(quote);
+        for c in s.as_ref().chars() {
+            if c == quote {
+                self.push(quote);
+            }
+            self.push(c);
         }
-        self.push('"');
-        self.push_str(s.as_ref());
-        self.push('"');
+        self.push(quote);
     }
 
-    fn push_table_name(&mut self, schema: &Option<String>, table: &str) {
+    fn push_table_name(&mut self, schema: &Option<String>, table: &str, dialect: Dialect) {
         if let Some(schema) = schema {
-            self.push_quoted(schema);
+            self.push_quoted(schema, dialect);
             self.push('.');
         }
-        self.push_quoted(table);
+        self.push_quoted(table, dialect);
     }
 
     fn push_sql<T: ToSql>(&mut self, sql: &T, dialect: Dialect) {
@@ -32,7 +40,7 @@ impl SqlExtension for String {
 
     fn push_sql_sequence<T: ToSql>(&mut self, sql: &[T], separator: &str, dialect: Dialect) {
         let mut first = true;
-        for s in sql.into_iter() {
+        for s in sql.iter() {
             if !first {
                 self.push_str(separator);
             }
@@ -41,18 +49,32 @@ impl SqlExtension for String {
         }
     }
 
-    fn push_quoted_sequence(&mut self, sql: &[String], separator: &str) {
+    fn push_quoted_sequence(&mut self, sql: &[String], separator: &str, dialect: Dialect) {
         let mut first = true;
         for s in sql {
             if !first {
                 self.push_str(separator);
             }
-            self.push_quoted(s);
+            self.push_quoted(s, dialect);
             first = false;
         }
     }
 }
 
+/// Write the column names of a RETURNING clause. `*` is written unquoted.
+pub(crate) fn push_returning(buf: &mut String, columns: &[String], dialect: Dialect) {
+    buf.push_str(" RETURNING ");
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            buf.push_str(", ");
+        }
+        match column.as_str() {
+            "*" => buf.push('*'),
+            column => buf.push_quoted(column, dialect),
+        }
+    }
+}
+
 pub fn pkey_column_names(schema: &str) -> Vec<String> {
     vec![
         format!("{}_id", schema),
@@ -61,3 +83,19 @@ pub fn pkey_column_names(schema: &str) -> Vec<String> {
         "uuid".to_string(),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_quoted() {
+        let mut buf = String::new();
+        buf.push_quoted(r#"my "col""#, Dialect::Postgres);
+        assert_eq!(buf, r#""my ""col""""#);
+
+        let mut buf = String::new();
+        buf.push_quoted("my `col`", Dialect::Mysql);
+        assert_eq!(buf, "`my ``col```");
+    }
+}
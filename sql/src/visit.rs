@@ -0,0 +1,700 @@
+//! Read-only traversal of query and schema trees.
+//!
+//! Implement `Visit` and override the methods for the nodes you are interested in. The default
+//! methods call the matching `walk_*` function, which visits the node's children; call it from
+//! an override to keep descending. Raw SQL, e.g. `Expr::Raw` and `OrderBy::column`, is not
+//! parsed, so references inside it are not visited.
+//!
+//! ```
+//! use sql::visit::Visit;
+//! use sql::{Dialect, Select};
+//!
+//! #[derive(Default)]
+//! struct Tables(Vec<String>);
+//!
+//! impl Visit for Tables {
+//!     fn visit_table_name(&mut self, _schema: Option<&str>, name: &str) {
+//!         self.0.push(name.to_string());
+//!     }
+//! }
+//!
+//! let select = Select::parse("SELECT * FROM a JOIN b USING (id)", Dialect::Postgres).unwrap();
+//! let mut tables = Tables::default();
+//! tables.visit_select(&select);
+//! assert_eq!(tables.0, vec!["a", "b"]);
+//! ```
+use crate::query::{
+    AlterAction, AlterColumnAction, AlterTable, Assignment, Compound, CompoundQuery, Conflict,
+    Criteria, Cte, CteQuery, Delete, Distinct, DropTable, Expr, FrameBound, From, Function,
+    GroupBy, Insert, Join, JoinTable, Lock, OnConflict, OrderBy, Over, Select, SelectColumn,
+    SelectExpression, Update, Values, Where,
+};
+use crate::schema::{Column, Constraint, GenerationValue, Index, Schema, Table, Type};
+
+pub trait Visit {
+    fn visit_select(&mut self, node: &Select) {
+        walk_select(self, node)
+    }
+
+    fn visit_compound(&mut self, node: &Compound) {
+        walk_compound(self, node)
+    }
+
+    fn visit_insert(&mut self, node: &Insert) {
+        walk_insert(self, node)
+    }
+
+    fn visit_update(&mut self, node: &Update) {
+        walk_update(self, node)
+    }
+
+    fn visit_delete(&mut self, node: &Delete) {
+        walk_delete(self, node)
+    }
+
+    fn visit_cte(&mut self, node: &Cte) {
+        walk_cte(self, node)
+    }
+
+    fn visit_select_column(&mut self, node: &SelectColumn) {
+        walk_select_column(self, node)
+    }
+
+    fn visit_from(&mut self, node: &From) {
+        walk_from(self, node)
+    }
+
+    fn visit_join(&mut self, node: &Join) {
+        walk_join(self, node)
+    }
+
+    fn visit_where(&mut self, node: &Where) {
+        walk_where(self, node)
+    }
+
+    fn visit_group_by(&mut self, node: &GroupBy) {
+        walk_group_by(self, node)
+    }
+
+    fn visit_order_by(&mut self, _node: &OrderBy) {}
+
+    fn visit_expr(&mut self, node: &Expr) {
+        walk_expr(self, node)
+    }
+
+    fn visit_function(&mut self, node: &Function) {
+        walk_function(self, node)
+    }
+
+    fn visit_over(&mut self, node: &Over) {
+        walk_over(self, node)
+    }
+
+    fn visit_lock(&mut self, node: &Lock) {
+        walk_lock(self, node)
+    }
+
+    /// A reference to a table, in a query or in DDL. CTE names used in FROM are visited too.
+    fn visit_table_name(&mut self, _schema: Option<&str>, _name: &str) {}
+
+    /// A column reference in an expression or a select list, or a column name in a statement,
+    /// e.g. an INSERT column list, an UPDATE SET target or a column definition, with the table
+    /// it belongs to, if any. Column definitions are visited by the table or ALTER TABLE that
+    /// contains them, not by `visit_column`.
+    fn visit_column_name(&mut self, _schema: Option<&str>, _table: Option<&str>, _column: &str) {}
+
+    fn visit_schema(&mut self, node: &Schema) {
+        walk_schema(self, node)
+    }
+
+    fn visit_table(&mut self, node: &Table) {
+        walk_table(self, node)
+    }
+
+    fn visit_column(&mut self, node: &Column) {
+        walk_column(self, node)
+    }
+
+    fn visit_constraint(&mut self, node: &Constraint) {
+        walk_constraint(self, node)
+    }
+
+    fn visit_type(&mut self, node: &Type) {
+        walk_type(self, node)
+    }
+
+    fn visit_index(&mut self, node: &Index) {
+        walk_index(self, node)
+    }
+
+    fn visit_alter_table(&mut self, node: &AlterTable) {
+        walk_alter_table(self, node)
+    }
+
+    fn visit_drop_table(&mut self, node: &DropTable) {
+        walk_drop_table(self, node)
+    }
+}
+
+pub fn walk_select<V: Visit + ?Sized>(v: &mut V, node: &Select) {
+    for cte in &node.ctes {
+        v.visit_cte(cte);
+    }
+    if let Distinct::On(exprs) = &node.distinct {
+        for expr in exprs {
+            v.visit_expr(expr);
+        }
+    }
+    for column in &node.columns {
+        v.visit_select_column(column);
+    }
+    if let Some(from) = &node.from {
+        v.visit_from(from);
+    }
+    for join in &node.join {
+        v.visit_join(join);
+    }
+    v.visit_where(&node.where_);
+    for group in &node.group {
+        v.visit_group_by(group);
+    }
+    v.visit_where(&node.having);
+    for window in &node.windows {
+        v.visit_over(&window.over);
+    }
+    for order in &node.order {
+        v.visit_order_by(order);
+    }
+    for lock in &node.locks {
+        v.visit_lock(lock);
+    }
+}
+
+/// Visit column names that can't be qualified, e.g. an INSERT column list.
+fn walk_column_names<'a, V: Visit + ?Sized>(
+    v: &mut V,
+    schema: Option<&str>,
+    table: Option<&str>,
+    columns: impl IntoIterator<Item = &'a String>,
+) {
+    for column in columns {
+        v.visit_column_name(schema, table, column);
+    }
+}
+
+fn walk_compound_query<V: Visit + ?Sized>(v: &mut V, node: &CompoundQuery) {
+    match node {
+        CompoundQuery::Select(select) => v.visit_select(select),
+        CompoundQuery::Compound(compound) => v.visit_compound(compound),
+    }
+}
+
+pub fn walk_compound<V: Visit + ?Sized>(v: &mut V, node: &Compound) {
+    walk_compound_query(v, &node.first);
+    for (_, query) in &node.rest {
+        walk_compound_query(v, query);
+    }
+    for order in &node.order {
+        v.visit_order_by(order);
+    }
+}
+
+pub fn walk_insert<V: Visit + ?Sized>(v: &mut V, node: &Insert) {
+    let (schema, table) = (node.schema.as_deref(), Some(node.table.as_str()));
+    v.visit_table_name(schema, &node.table);
+    walk_column_names(v, schema, table, &node.columns);
+    match &node.values {
+        Values::Values(rows) => {
+            for row in rows {
+                for expr in &row.0 {
+                    v.visit_expr(expr);
+                }
+            }
+        }
+        Values::Select(select) => v.visit_select(select),
+        Values::Compound(compound) => v.visit_compound(compound),
+        Values::DefaultValues => {}
+    }
+    match &node.on_conflict {
+        OnConflict::DoUpdate {
+            conflict,
+            updates,
+            where_,
+        } => {
+            if let Conflict::Columns(columns) = conflict {
+                walk_column_names(v, schema, table, columns);
+            }
+            for (column, expr) in updates {
+                v.visit_column_name(schema, table, column);
+                v.visit_expr(expr);
+            }
+            v.visit_where(where_);
+        }
+        OnConflict::DoUpdateAllRows {
+            conflict,
+            alternate_values,
+            ignore_columns,
+            where_,
+        } => {
+            if let Conflict::Columns(columns) = conflict {
+                walk_column_names(v, schema, table, columns);
+            }
+            for (column, expr) in alternate_values {
+                v.visit_column_name(schema, table, column);
+                v.visit_expr(expr);
+            }
+            walk_column_names(v, schema, table, ignore_columns);
+            v.visit_where(where_);
+        }
+        OnConflict::Ignore | OnConflict::Abort | OnConflict::Replace => {}
+    }
+    walk_column_names(v, schema, table, &node.returning);
+}
+
+pub fn walk_update<V: Visit + ?Sized>(v: &mut V, node: &Update) {
+    for cte in &node.ctes {
+        v.visit_cte(cte);
+    }
+    let (schema, table) = (node.schema.as_deref(), Some(node.table.as_str()));
+    v.visit_table_name(schema, &node.table);
+    for assignment in &node.values {
+        match assignment {
+            Assignment::Column(column, expr) => {
+                v.visit_column_name(schema, table, column);
+                v.visit_expr(expr);
+            }
+            Assignment::Row(columns, expr) => {
+                walk_column_names(v, schema, table, columns);
+                v.visit_expr(expr);
+            }
+        }
+    }
+    for from in &node.from {
+        v.visit_from(from);
+    }
+    for join in &node.join {
+        v.visit_join(join);
+    }
+    v.visit_where(&node.where_);
+    for column in &node.returning {
+        v.visit_select_column(column);
+    }
+    for order in &node.order {
+        v.visit_order_by(order);
+    }
+}
+
+pub fn walk_delete<V: Visit + ?Sized>(v: &mut V, node: &Delete) {
+    for cte in &node.ctes {
+        v.visit_cte(cte);
+    }
+    v.visit_table_name(node.schema.as_deref(), &node.table);
+    for target in &node.targets {
+        v.visit_table_name(None, target);
+    }
+    for from in &node.using {
+        v.visit_from(from);
+    }
+    for join in &node.join {
+        v.visit_join(join);
+    }
+    v.visit_where(&node.where_);
+    walk_column_names(v, node.schema.as_deref(), Some(&node.table), &node.returning);
+    for order in &node.order {
+        v.visit_order_by(order);
+    }
+}
+
+pub fn walk_cte<V: Visit + ?Sized>(v: &mut V, node: &Cte) {
+    walk_column_names(v, None, Some(&node.name), &node.columns);
+    match &node.query {
+        CteQuery::Select(select) => v.visit_select(select),
+        CteQuery::Compound(compound) => v.visit_compound(compound),
+        CteQuery::Insert(insert) => v.visit_insert(insert),
+        CteQuery::Update(update) => v.visit_update(update),
+        CteQuery::Delete(delete) => v.visit_delete(delete),
+        CteQuery::Raw(_) => {}
+    }
+}
+
+pub fn walk_select_column<V: Visit + ?Sized>(v: &mut V, node: &SelectColumn) {
+    match &node.expression {
+        SelectExpression::Column {
+            schema,
+            table,
+            column,
+        } => v.visit_column_name(schema.as_deref(), table.as_deref(), column),
+        SelectExpression::Expr(expr) => v.visit_expr(expr),
+        SelectExpression::Raw(_) => {}
+    }
+}
+
+pub fn walk_from<V: Visit + ?Sized>(v: &mut V, node: &From) {
+    match node {
+        From::Table { schema, table, .. } => v.visit_table_name(schema.as_deref(), table),
+        From::Select { query, .. } => v.visit_select(query),
+        From::Compound { query, .. } => v.visit_compound(query),
+        From::Function { function, .. } => v.visit_function(function),
+        From::Values { rows, .. } => {
+            for row in rows {
+                for expr in &row.0 {
+                    v.visit_expr(expr);
+                }
+            }
+        }
+    }
+}
+
+pub fn walk_join<V: Visit + ?Sized>(v: &mut V, node: &Join) {
+    match &node.table {
+        JoinTable::Select(select) => v.visit_select(select),
+        JoinTable::Compound(compound) => v.visit_compound(compound),
+        JoinTable::Table { schema, table } => v.visit_table_name(schema.as_deref(), table),
+        JoinTable::Function(function) => v.visit_function(function),
+        JoinTable::Values(rows) => {
+            for row in rows {
+                for expr in &row.0 {
+                    v.visit_expr(expr);
+                }
+            }
+        }
+    }
+    match &node.criteria {
+        Criteria::On(where_) => v.visit_where(where_),
+        Criteria::Using(columns) => walk_column_names(v, None, None, columns),
+        Criteria::Natural => {}
+    }
+}
+
+pub fn walk_where<V: Visit + ?Sized>(v: &mut V, node: &Where) {
+    match node {
+        Where::And(terms) | Where::Or(terms) => {
+            for term in terms {
+                v.visit_where(term);
+            }
+        }
+        #[allow(deprecated)]
+        Where::Raw(_) => {}
+        Where::Expr(expr) => v.visit_expr(expr),
+    }
+}
+
+pub fn walk_group_by<V: Visit + ?Sized>(v: &mut V, node: &GroupBy) {
+    match node {
+        GroupBy::Expr(expr) => v.visit_expr(expr),
+        GroupBy::Position(_) => {}
+        GroupBy::Rollup(exprs) | GroupBy::Cube(exprs) => {
+            for expr in exprs {
+                v.visit_expr(expr);
+            }
+        }
+        GroupBy::GroupingSets(sets) => {
+            for expr in sets.iter().flatten() {
+                v.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_expr<V: Visit + ?Sized>(v: &mut V, node: &Expr) {
+    match node {
+        Expr::Case(case) => {
+            for (condition, value) in &case.cases {
+                v.visit_expr(condition);
+                v.visit_expr(value);
+            }
+            if let Some(els) = &case.els {
+                v.visit_expr(els);
+            }
+        }
+        Expr::And(exprs) | Expr::Or(exprs) | Expr::Row(exprs) => {
+            for expr in exprs {
+                v.visit_expr(expr);
+            }
+        }
+        Expr::Raw(_) | Expr::Param(_) | Expr::Literal(_) => {}
+        Expr::NotDistinctFrom(left, right) | Expr::BinOp(_, left, right) => {
+            v.visit_expr(left);
+            v.visit_expr(right);
+        }
+        Expr::Column {
+            schema,
+            table,
+            column,
+        } => v.visit_column_name(schema.as_deref(), table.as_deref(), column),
+        Expr::Not(expr) | Expr::Neg(expr) | Expr::IsNull(expr) | Expr::IsNotNull(expr) => {
+            v.visit_expr(expr)
+        }
+        Expr::In(expr, list) | Expr::NotIn(expr, list) => {
+            v.visit_expr(expr);
+            for item in list {
+                v.visit_expr(item);
+            }
+        }
+        Expr::Between(expr, low, high) | Expr::NotBetween(expr, low, high) => {
+            v.visit_expr(expr);
+            v.visit_expr(low);
+            v.visit_expr(high);
+        }
+        Expr::Function(function) => v.visit_function(function),
+        Expr::Exists(select) | Expr::Subquery(select) => v.visit_select(select),
+        Expr::InSelect(expr, select)
+        | Expr::NotInSelect(expr, select)
+        | Expr::Any(_, expr, select)
+        | Expr::All(_, expr, select) => {
+            v.visit_expr(expr);
+            v.visit_select(select);
+        }
+        Expr::Cast(expr, typ) => {
+            v.visit_expr(expr);
+            v.visit_type(typ);
+        }
+    }
+}
+
+pub fn walk_function<V: Visit + ?Sized>(v: &mut V, node: &Function) {
+    for arg in &node.args {
+        v.visit_expr(arg);
+    }
+    for order in &node.order {
+        v.visit_order_by(order);
+    }
+    if let Some(filter) = &node.filter {
+        v.visit_expr(filter);
+    }
+    if let Some(over) = &node.over {
+        v.visit_over(over);
+    }
+}
+
+pub fn walk_over<V: Visit + ?Sized>(v: &mut V, node: &Over) {
+    for expr in &node.partition {
+        v.visit_expr(expr);
+    }
+    for order in &node.order {
+        v.visit_order_by(order);
+    }
+    if let Some(frame) = &node.frame {
+        for bound in std::iter::once(&frame.start).chain(&frame.end) {
+            if let FrameBound::Preceding(expr) | FrameBound::Following(expr) = bound {
+                v.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_lock<V: Visit + ?Sized>(v: &mut V, node: &Lock) {
+    for table in &node.of {
+        v.visit_table_name(None, table);
+    }
+}
+
+pub fn walk_schema<V: Visit + ?Sized>(v: &mut V, node: &Schema) {
+    for table in &node.tables {
+        v.visit_table(table);
+    }
+    for index in &node.indexes {
+        v.visit_index(index);
+    }
+}
+
+pub fn walk_table<V: Visit + ?Sized>(v: &mut V, node: &Table) {
+    v.visit_table_name(node.schema.as_deref(), &node.name);
+    for column in &node.columns {
+        v.visit_column_name(node.schema.as_deref(), Some(&node.name), &column.name);
+        v.visit_column(column);
+    }
+}
+
+pub fn walk_column<V: Visit + ?Sized>(v: &mut V, node: &Column) {
+    v.visit_type(&node.typ);
+    if let Some(default) = &node.default {
+        v.visit_expr(default);
+    }
+    if let Some(constraint) = &node.constraint {
+        v.visit_constraint(constraint);
+    }
+    if let Some(generated) = &node.generated
+        && let GenerationValue::Expr(expr) = &generated.value
+    {
+        v.visit_expr(expr);
+    }
+}
+
+pub fn walk_constraint<V: Visit + ?Sized>(v: &mut V, node: &Constraint) {
+    match node {
+        Constraint::ForeignKey(fk) => {
+            let (schema, table) = (fk.schema.as_deref(), Some(fk.table.as_str()));
+            v.visit_table_name(schema, &fk.table);
+            walk_column_names(v, schema, table, &fk.columns);
+        }
+    }
+}
+
+pub fn walk_type<V: Visit + ?Sized>(v: &mut V, node: &Type) {
+    if let Type::Array(inner) = node {
+        v.visit_type(inner);
+    }
+}
+
+pub fn walk_index<V: Visit + ?Sized>(v: &mut V, node: &Index) {
+    let (schema, table) = (node.schema.as_deref(), Some(node.table.as_str()));
+    v.visit_table_name(schema, &node.table);
+    walk_column_names(v, schema, table, &node.columns);
+}
+
+pub fn walk_alter_table<V: Visit + ?Sized>(v: &mut V, node: &AlterTable) {
+    let (schema, table) = (node.schema.as_deref(), Some(node.name.as_str()));
+    v.visit_table_name(schema, &node.name);
+    for action in &node.actions {
+        match action {
+            AlterAction::AddColumn { column } => {
+                v.visit_column_name(schema, table, &column.name);
+                v.visit_column(column);
+            }
+            AlterAction::AlterColumn { name, action } => {
+                v.visit_column_name(schema, table, name);
+                match action {
+                    AlterColumnAction::SetType { typ, .. } => v.visit_type(typ),
+                    AlterColumnAction::SetNullable(_) => {}
+                }
+            }
+            AlterAction::AddConstraint {
+                column, constraint, ..
+            } => {
+                v.visit_column_name(schema, table, column);
+                v.visit_constraint(constraint);
+            }
+        }
+    }
+}
+
+pub fn walk_drop_table<V: Visit + ?Sized>(v: &mut V, node: &DropTable) {
+    v.visit_table_name(node.schema.as_deref(), &node.name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dialect, FromDdl};
+
+    #[derive(Default)]
+    struct References {
+        tables: Vec<String>,
+        columns: Vec<String>,
+    }
+
+    impl Visit for References {
+        fn visit_table_name(&mut self, _schema: Option<&str>, name: &str) {
+            self.tables.push(name.to_string());
+        }
+
+        fn visit_column_name(&mut self, _schema: Option<&str>, table: Option<&str>, column: &str) {
+            match table {
+                Some(table) => self.columns.push(format!("{}.{}", table, column)),
+                None => self.columns.push(column.to_string()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_references() {
+        let sql = "WITH recent AS (SELECT user_id FROM orders WHERE created_at > now()) \
+            SELECT u.id, (SELECT count(*) FROM posts p WHERE p.user_id = u.id) \
+            FROM users u JOIN recent r ON r.user_id = u.id \
+            WHERE u.id IN (SELECT user_id FROM admins) ORDER BY u.id";
+        let select = Select::parse(sql, Dialect::Postgres).unwrap();
+        let mut references = References::default();
+        references.visit_select(&select);
+        assert_eq!(
+            references.tables,
+            vec!["orders", "posts", "users", "recent", "admins"]
+        );
+        assert_eq!(
+            references.columns,
+            vec![
+                "user_id",
+                "created_at",
+                "u.id",
+                "p.user_id",
+                "u.id",
+                "r.user_id",
+                "u.id",
+                "u.id",
+                "user_id"
+            ]
+        );
+
+        let mut references = References::default();
+        let insert = Insert::parse(
+            "INSERT INTO archive (id) SELECT id FROM users WHERE deleted",
+            Dialect::Postgres,
+        )
+        .unwrap();
+        references.visit_insert(&insert);
+        assert_eq!(references.tables, vec!["archive", "users"]);
+        assert_eq!(references.columns, vec!["archive.id", "id", "deleted"]);
+
+        let mut references = References::default();
+        let insert = Insert::parse(
+            "INSERT INTO users (id, name) VALUES (1, 'a') ON CONFLICT (id) DO UPDATE SET name = excluded.name RETURNING id",
+            Dialect::Postgres,
+        )
+        .unwrap();
+        references.visit_insert(&insert);
+        assert_eq!(
+            references.columns,
+            vec![
+                "users.id",
+                "users.name",
+                "users.id",
+                "users.name",
+                "excluded.name",
+                "users.id"
+            ]
+        );
+
+        let mut references = References::default();
+        let update = Update::parse(
+            "UPDATE users SET name = 'a', (x, y) = (1, 2) WHERE id = 1",
+            Dialect::Postgres,
+        )
+        .unwrap();
+        references.visit_update(&update);
+        assert_eq!(
+            references.columns,
+            vec!["users.name", "users.x", "users.y", "id"]
+        );
+
+        let mut references = References::default();
+        let select = Select::parse(
+            "SELECT * FROM a JOIN b USING (id) FOR UPDATE OF a",
+            Dialect::Postgres,
+        )
+        .unwrap();
+        references.visit_select(&select);
+        assert_eq!(references.tables, vec!["a", "b", "a"]);
+        assert_eq!(references.columns, vec!["id"]);
+
+        let mut references = References::default();
+        let delete = Delete::parse(
+            "DELETE p, c FROM posts p JOIN comments c ON c.post_id = p.id",
+            Dialect::Mysql,
+        )
+        .unwrap();
+        references.visit_delete(&delete);
+        assert_eq!(references.tables, vec!["posts", "c", "comments"]);
+
+        let mut references = References::default();
+        let sql = "CREATE TABLE posts (id int NOT NULL, user_id int REFERENCES users(id));\n\
+            CREATE INDEX posts_user_id_idx ON posts (user_id);\n\
+            ALTER TABLE posts ALTER COLUMN user_id SET NOT NULL;";
+        let schema = Schema::try_from_ddl(sql, Dialect::Postgres).unwrap();
+        references.visit_schema(&schema);
+        assert_eq!(references.tables, vec!["posts", "users", "posts"]);
+        assert_eq!(
+            references.columns,
+            vec!["posts.id", "posts.user_id", "users.id", "posts.user_id"]
+        );
+    }
+}
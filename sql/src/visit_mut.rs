@@ -0,0 +1,701 @@
+//! Traversal of query and schema trees that can modify them in place.
+//!
+//! Works like `Visit`, with mutable references: override the methods for the nodes you want to
+//! rewrite, and call the matching `walk_*` function to keep descending.
+//!
+//! ```
+//! use sql::visit_mut::VisitMut;
+//! use sql::{Dialect, Select, ToSql};
+//!
+//! /// Rename a column everywhere it is referenced.
+//! struct Rename<'a>(&'a str, &'a str);
+//!
+//! impl VisitMut for Rename<'_> {
+//!     fn visit_column_name(
+//!         &mut self,
+//!         _schema: &mut Option<String>,
+//!         _table: &mut Option<String>,
+//!         column: &mut String,
+//!     ) {
+//!         if column == self.0 {
+//!             *column = self.1.to_string();
+//!         }
+//!     }
+//! }
+//!
+//! let mut select = Select::parse("SELECT name FROM users WHERE name LIKE 'k%'", Dialect::Postgres).unwrap();
+//! Rename("name", "full_name").visit_select(&mut select);
+//! assert_eq!(
+//!     select.to_sql(Dialect::Postgres),
+//!     r#"SELECT "full_name" FROM "users" WHERE "full_name" LIKE 'k%'"#
+//! );
+//! ```
+use crate::query::{
+    AlterAction, AlterColumnAction, AlterTable, Assignment, Compound, CompoundQuery, Conflict,
+    Criteria, Cte, CteQuery, Delete, Distinct, DropTable, Expr, FrameBound, From, Function,
+    GroupBy, Insert, Join, JoinTable, Lock, OnConflict, OrderBy, Over, Select, SelectColumn,
+    SelectExpression, Update, Values, Where,
+};
+use crate::schema::{Column, Constraint, GenerationValue, Index, Schema, Table, Type};
+
+pub trait VisitMut {
+    fn visit_select(&mut self, node: &mut Select) {
+        walk_select(self, node)
+    }
+
+    fn visit_compound(&mut self, node: &mut Compound) {
+        walk_compound(self, node)
+    }
+
+    fn visit_insert(&mut self, node: &mut Insert) {
+        walk_insert(self, node)
+    }
+
+    fn visit_update(&mut self, node: &mut Update) {
+        walk_update(self, node)
+    }
+
+    fn visit_delete(&mut self, node: &mut Delete) {
+        walk_delete(self, node)
+    }
+
+    fn visit_cte(&mut self, node: &mut Cte) {
+        walk_cte(self, node)
+    }
+
+    fn visit_select_column(&mut self, node: &mut SelectColumn) {
+        walk_select_column(self, node)
+    }
+
+    fn visit_from(&mut self, node: &mut From) {
+        walk_from(self, node)
+    }
+
+    fn visit_join(&mut self, node: &mut Join) {
+        walk_join(self, node)
+    }
+
+    fn visit_where(&mut self, node: &mut Where) {
+        walk_where(self, node)
+    }
+
+    fn visit_group_by(&mut self, node: &mut GroupBy) {
+        walk_group_by(self, node)
+    }
+
+    fn visit_order_by(&mut self, _node: &mut OrderBy) {}
+
+    fn visit_expr(&mut self, node: &mut Expr) {
+        walk_expr(self, node)
+    }
+
+    fn visit_function(&mut self, node: &mut Function) {
+        walk_function(self, node)
+    }
+
+    fn visit_over(&mut self, node: &mut Over) {
+        walk_over(self, node)
+    }
+
+    fn visit_lock(&mut self, node: &mut Lock) {
+        walk_lock(self, node)
+    }
+
+    /// A reference to a table, in a query or in DDL. CTE names used in FROM are visited too.
+    fn visit_table_name(&mut self, _schema: &mut Option<String>, _name: &mut String) {}
+
+    /// A column reference in an expression or a select list, or a column name in a statement,
+    /// e.g. an INSERT column list, an UPDATE SET target or a column definition.
+    ///
+    /// Column names in statements are visited with the table they belong to, if any, and
+    /// changes to their schema and table are ignored. Column definitions are visited by the
+    /// table or ALTER TABLE that contains them, not by `visit_column`.
+    fn visit_column_name(
+        &mut self,
+        _schema: &mut Option<String>,
+        _table: &mut Option<String>,
+        _column: &mut String,
+    ) {
+    }
+
+    fn visit_schema(&mut self, node: &mut Schema) {
+        walk_schema(self, node)
+    }
+
+    fn visit_table(&mut self, node: &mut Table) {
+        walk_table(self, node)
+    }
+
+    fn visit_column(&mut self, node: &mut Column) {
+        walk_column(self, node)
+    }
+
+    fn visit_constraint(&mut self, node: &mut Constraint) {
+        walk_constraint(self, node)
+    }
+
+    fn visit_type(&mut self, node: &mut Type) {
+        walk_type(self, node)
+    }
+
+    fn visit_index(&mut self, node: &mut Index) {
+        walk_index(self, node)
+    }
+
+    fn visit_alter_table(&mut self, node: &mut AlterTable) {
+        walk_alter_table(self, node)
+    }
+
+    fn visit_drop_table(&mut self, node: &mut DropTable) {
+        walk_drop_table(self, node)
+    }
+}
+
+pub fn walk_select<V: VisitMut + ?Sized>(v: &mut V, node: &mut Select) {
+    for cte in &mut node.ctes {
+        v.visit_cte(cte);
+    }
+    if let Distinct::On(exprs) = &mut node.distinct {
+        for expr in exprs {
+            v.visit_expr(expr);
+        }
+    }
+    for column in &mut node.columns {
+        v.visit_select_column(column);
+    }
+    if let Some(from) = &mut node.from {
+        v.visit_from(from);
+    }
+    for join in &mut node.join {
+        v.visit_join(join);
+    }
+    v.visit_where(&mut node.where_);
+    for group in &mut node.group {
+        v.visit_group_by(group);
+    }
+    v.visit_where(&mut node.having);
+    for window in &mut node.windows {
+        v.visit_over(&mut window.over);
+    }
+    for order in &mut node.order {
+        v.visit_order_by(order);
+    }
+    for lock in &mut node.locks {
+        v.visit_lock(lock);
+    }
+}
+
+/// Visit column names that can't be qualified, e.g. an INSERT column list.
+fn walk_column_names<'a, V: VisitMut + ?Sized>(
+    v: &mut V,
+    schema: &Option<String>,
+    table: Option<&str>,
+    columns: impl IntoIterator<Item = &'a mut String>,
+) {
+    for column in columns {
+        v.visit_column_name(&mut schema.clone(), &mut table.map(str::to_string), column);
+    }
+}
+
+fn walk_compound_query<V: VisitMut + ?Sized>(v: &mut V, node: &mut CompoundQuery) {
+    match node {
+        CompoundQuery::Select(select) => v.visit_select(select),
+        CompoundQuery::Compound(compound) => v.visit_compound(compound),
+    }
+}
+
+pub fn walk_compound<V: VisitMut + ?Sized>(v: &mut V, node: &mut Compound) {
+    walk_compound_query(v, &mut node.first);
+    for (_, query) in &mut node.rest {
+        walk_compound_query(v, query);
+    }
+    for order in &mut node.order {
+        v.visit_order_by(order);
+    }
+}
+
+pub fn walk_insert<V: VisitMut + ?Sized>(v: &mut V, node: &mut Insert) {
+    v.visit_table_name(&mut node.schema, &mut node.table);
+    let table = Some(node.table.as_str());
+    walk_column_names(v, &node.schema, table, &mut node.columns);
+    match &mut node.values {
+        Values::Values(rows) => {
+            for row in rows {
+                for expr in &mut row.0 {
+                    v.visit_expr(expr);
+                }
+            }
+        }
+        Values::Select(select) => v.visit_select(select),
+        Values::Compound(compound) => v.visit_compound(compound),
+        Values::DefaultValues => {}
+    }
+    match &mut node.on_conflict {
+        OnConflict::DoUpdate {
+            conflict,
+            updates,
+            where_,
+        } => {
+            if let Conflict::Columns(columns) = conflict {
+                walk_column_names(v, &node.schema, table, columns);
+            }
+            for (column, expr) in updates {
+                walk_column_names(v, &node.schema, table, [column]);
+                v.visit_expr(expr);
+            }
+            v.visit_where(where_);
+        }
+        OnConflict::DoUpdateAllRows {
+            conflict,
+            alternate_values,
+            ignore_columns,
+            where_,
+        } => {
+            if let Conflict::Columns(columns) = conflict {
+                walk_column_names(v, &node.schema, table, columns);
+            }
+            *alternate_values = std::mem::take(alternate_values)
+                .into_iter()
+                .map(|(mut column, mut expr)| {
+                    walk_column_names(v, &node.schema, table, [&mut column]);
+                    v.visit_expr(&mut expr);
+                    (column, expr)
+                })
+                .collect();
+            walk_column_names(v, &node.schema, table, ignore_columns);
+            v.visit_where(where_);
+        }
+        OnConflict::Ignore | OnConflict::Abort | OnConflict::Replace => {}
+    }
+    walk_column_names(v, &node.schema, table, &mut node.returning);
+}
+
+pub fn walk_update<V: VisitMut + ?Sized>(v: &mut V, node: &mut Update) {
+    for cte in &mut node.ctes {
+        v.visit_cte(cte);
+    }
+    v.visit_table_name(&mut node.schema, &mut node.table);
+    let table = Some(node.table.as_str());
+    for assignment in &mut node.values {
+        match assignment {
+            Assignment::Column(column, expr) => {
+                walk_column_names(v, &node.schema, table, [column]);
+                v.visit_expr(expr);
+            }
+            Assignment::Row(columns, expr) => {
+                walk_column_names(v, &node.schema, table, columns);
+                v.visit_expr(expr);
+            }
+        }
+    }
+    for from in &mut node.from {
+        v.visit_from(from);
+    }
+    for join in &mut node.join {
+        v.visit_join(join);
+    }
+    v.visit_where(&mut node.where_);
+    for column in &mut node.returning {
+        v.visit_select_column(column);
+    }
+    for order in &mut node.order {
+        v.visit_order_by(order);
+    }
+}
+
+pub fn walk_delete<V: VisitMut + ?Sized>(v: &mut V, node: &mut Delete) {
+    for cte in &mut node.ctes {
+        v.visit_cte(cte);
+    }
+    v.visit_table_name(&mut node.schema, &mut node.table);
+    for target in &mut node.targets {
+        v.visit_table_name(&mut None, target);
+    }
+    for from in &mut node.using {
+        v.visit_from(from);
+    }
+    for join in &mut node.join {
+        v.visit_join(join);
+    }
+    v.visit_where(&mut node.where_);
+    walk_column_names(v, &node.schema, Some(&node.table), &mut node.returning);
+    for order in &mut node.order {
+        v.visit_order_by(order);
+    }
+}
+
+pub fn walk_cte<V: VisitMut + ?Sized>(v: &mut V, node: &mut Cte) {
+    walk_column_names(v, &None, Some(&node.name), &mut node.columns);
+    match &mut node.query {
+        CteQuery::Select(select) => v.visit_select(select),
+        CteQuery::Compound(compound) => v.visit_compound(compound),
+        CteQuery::Insert(insert) => v.visit_insert(insert),
+        CteQuery::Update(update) => v.visit_update(update),
+        CteQuery::Delete(delete) => v.visit_delete(delete),
+        CteQuery::Raw(_) => {}
+    }
+}
+
+pub fn walk_select_column<V: VisitMut + ?Sized>(v: &mut V, node: &mut SelectColumn) {
+    match &mut node.expression {
+        SelectExpression::Column {
+            schema,
+            table,
+            column,
+        } => v.visit_column_name(schema, table, column),
+        SelectExpression::Expr(expr) => v.visit_expr(expr),
+        SelectExpression::Raw(_) => {}
+    }
+}
+
+pub fn walk_from<V: VisitMut + ?Sized>(v: &mut V, node: &mut From) {
+    match node {
+        From::Table { schema, table, .. } => v.visit_table_name(schema, table),
+        From::Select { query, .. } => v.visit_select(query),
+        From::Compound { query, .. } => v.visit_compound(query),
+        From::Function { function, .. } => v.visit_function(function),
+        From::Values { rows, .. } => {
+            for row in rows {
+                for expr in &mut row.0 {
+                    v.visit_expr(expr);
+                }
+            }
+        }
+    }
+}
+
+pub fn walk_join<V: VisitMut + ?Sized>(v: &mut V, node: &mut Join) {
+    match &mut node.table {
+        JoinTable::Select(select) => v.visit_select(select),
+        JoinTable::Compound(compound) => v.visit_compound(compound),
+        JoinTable::Table { schema, table } => v.visit_table_name(schema, table),
+        JoinTable::Function(function) => v.visit_function(function),
+        JoinTable::Values(rows) => {
+            for row in rows {
+                for expr in &mut row.0 {
+                    v.visit_expr(expr);
+                }
+            }
+        }
+    }
+    match &mut node.criteria {
+        Criteria::On(where_) => v.visit_where(where_),
+        Criteria::Using(columns) => walk_column_names(v, &None, None, columns),
+        Criteria::Natural => {}
+    }
+}
+
+pub fn walk_where<V: VisitMut + ?Sized>(v: &mut V, node: &mut Where) {
+    match node {
+        Where::And(terms) | Where::Or(terms) => {
+            for term in terms {
+                v.visit_where(term);
+            }
+        }
+        #[allow(deprecated)]
+        Where::Raw(_) => {}
+        Where::Expr(expr) => v.visit_expr(expr),
+    }
+}
+
+pub fn walk_group_by<V: VisitMut + ?Sized>(v: &mut V, node: &mut GroupBy) {
+    match node {
+        GroupBy::Expr(expr) => v.visit_expr(expr),
+        GroupBy::Position(_) => {}
+        GroupBy::Rollup(exprs) | GroupBy::Cube(exprs) => {
+            for expr in exprs {
+                v.visit_expr(expr);
+            }
+        }
+        GroupBy::GroupingSets(sets) => {
+            for expr in sets.iter_mut().flatten() {
+                v.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_expr<V: VisitMut + ?Sized>(v: &mut V, node: &mut Expr) {
+    match node {
+        Expr::Case(case) => {
+            for (condition, value) in &mut case.cases {
+                v.visit_expr(condition);
+                v.visit_expr(value);
+            }
+            if let Some(els) = &mut case.els {
+                v.visit_expr(els);
+            }
+        }
+        Expr::And(exprs) | Expr::Or(exprs) | Expr::Row(exprs) => {
+            for expr in exprs {
+                v.visit_expr(expr);
+            }
+        }
+        Expr::Raw(_) | Expr::Param(_) | Expr::Literal(_) => {}
+        Expr::NotDistinctFrom(left, right) | Expr::BinOp(_, left, right) => {
+            v.visit_expr(left);
+            v.visit_expr(right);
+        }
+        Expr::Column {
+            schema,
+            table,
+            column,
+        } => v.visit_column_name(schema, table, column),
+        Expr::Not(expr) | Expr::Neg(expr) | Expr::IsNull(expr) | Expr::IsNotNull(expr) => {
+            v.visit_expr(expr)
+        }
+        Expr::In(expr, list) | Expr::NotIn(expr, list) => {
+            v.visit_expr(expr);
+            for item in list {
+                v.visit_expr(item);
+            }
+        }
+        Expr::Between(expr, low, high) | Expr::NotBetween(expr, low, high) => {
+            v.visit_expr(expr);
+            v.visit_expr(low);
+            v.visit_expr(high);
+        }
+        Expr::Function(function) => v.visit_function(function),
+        Expr::Exists(select) | Expr::Subquery(select) => v.visit_select(select),
+        Expr::InSelect(expr, select)
+        | Expr::NotInSelect(expr, select)
+        | Expr::Any(_, expr, select)
+        | Expr::All(_, expr, select) => {
+            v.visit_expr(expr);
+            v.visit_select(select);
+        }
+        Expr::Cast(expr, typ) => {
+            v.visit_expr(expr);
+            v.visit_type(typ);
+        }
+    }
+}
+
+pub fn walk_function<V: VisitMut + ?Sized>(v: &mut V, node: &mut Function) {
+    for arg in &mut node.args {
+        v.visit_expr(arg);
+    }
+    for order in &mut node.order {
+        v.visit_order_by(order);
+    }
+    if let Some(filter) = &mut node.filter {
+        v.visit_expr(filter);
+    }
+    if let Some(over) = &mut node.over {
+        v.visit_over(over);
+    }
+}
+
+pub fn walk_over<V: VisitMut + ?Sized>(v: &mut V, node: &mut Over) {
+    for expr in &mut node.partition {
+        v.visit_expr(expr);
+    }
+    for order in &mut node.order {
+        v.visit_order_by(order);
+    }
+    if let Some(frame) = &mut node.frame {
+        for bound in std::iter::once(&mut frame.start).chain(&mut frame.end) {
+            if let FrameBound::Preceding(expr) | FrameBound::Following(expr) = bound {
+                v.visit_expr(expr);
+            }
+        }
+    }
+}
+
+pub fn walk_lock<V: VisitMut + ?Sized>(v: &mut V, node: &mut Lock) {
+    for table in &mut node.of {
+        v.visit_table_name(&mut None, table);
+    }
+}
+
+pub fn walk_schema<V: VisitMut + ?Sized>(v: &mut V, node: &mut Schema) {
+    for table in &mut node.tables {
+        v.visit_table(table);
+    }
+    for index in &mut node.indexes {
+        v.visit_index(index);
+    }
+}
+
+pub fn walk_table<V: VisitMut + ?Sized>(v: &mut V, node: &mut Table) {
+    v.visit_table_name(&mut node.schema, &mut node.name);
+    for column in &mut node.columns {
+        walk_column_names(v, &node.schema, Some(&node.name), [&mut column.name]);
+        v.visit_column(column);
+    }
+}
+
+pub fn walk_column<V: VisitMut + ?Sized>(v: &mut V, node: &mut Column) {
+    v.visit_type(&mut node.typ);
+    if let Some(default) = &mut node.default {
+        v.visit_expr(default);
+    }
+    if let Some(constraint) = &mut node.constraint {
+        v.visit_constraint(constraint);
+    }
+    if let Some(generated) = &mut node.generated
+        && let GenerationValue::Expr(expr) = &mut generated.value
+    {
+        v.visit_expr(expr);
+    }
+}
+
+pub fn walk_constraint<V: VisitMut + ?Sized>(v: &mut V, node: &mut Constraint) {
+    match node {
+        Constraint::ForeignKey(fk) => {
+            v.visit_table_name(&mut fk.schema, &mut fk.table);
+            walk_column_names(v, &fk.schema, Some(&fk.table), &mut fk.columns);
+        }
+    }
+}
+
+pub fn walk_type<V: VisitMut + ?Sized>(v: &mut V, node: &mut Type) {
+    if let Type::Array(inner) = node {
+        v.visit_type(inner);
+    }
+}
+
+pub fn walk_index<V: VisitMut + ?Sized>(v: &mut V, node: &mut Index) {
+    v.visit_table_name(&mut node.schema, &mut node.table);
+    walk_column_names(v, &node.schema, Some(&node.table), &mut node.columns);
+}
+
+pub fn walk_alter_table<V: VisitMut + ?Sized>(v: &mut V, node: &mut AlterTable) {
+    v.visit_table_name(&mut node.schema, &mut node.name);
+    let (schema, table) = (&node.schema, Some(node.name.as_str()));
+    for action in &mut node.actions {
+        match action {
+            AlterAction::AddColumn { column } => {
+                walk_column_names(v, schema, table, [&mut column.name]);
+                v.visit_column(column);
+            }
+            AlterAction::AlterColumn { name, action } => {
+                walk_column_names(v, schema, table, [name]);
+                match action {
+                    AlterColumnAction::SetType { typ, .. } => v.visit_type(typ),
+                    AlterColumnAction::SetNullable(_) => {}
+                }
+            }
+            AlterAction::AddConstraint {
+                column, constraint, ..
+            } => {
+                walk_column_names(v, schema, table, [column]);
+                v.visit_constraint(constraint);
+            }
+        }
+    }
+}
+
+pub fn walk_drop_table<V: VisitMut + ?Sized>(v: &mut V, node: &mut DropTable) {
+    v.visit_table_name(&mut node.schema, &mut node.name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dialect, FromDdl, ToSql};
+
+    /// Qualify unqualified column references with a table name.
+    struct Qualify(&'static str);
+
+    impl VisitMut for Qualify {
+        fn visit_column_name(
+            &mut self,
+            _schema: &mut Option<String>,
+            table: &mut Option<String>,
+            _column: &mut String,
+        ) {
+            table.get_or_insert_with(|| self.0.to_string());
+        }
+
+        // Columns in subqueries belong to their own tables.
+        fn visit_select(&mut self, _node: &mut Select) {}
+    }
+
+    #[test]
+    fn test_qualify() {
+        let sql = "UPDATE users SET score = score + 1 WHERE id = $1 AND NOT EXISTS (SELECT 1 FROM bans WHERE user_id = id) RETURNING id";
+        let mut update = Update::parse(sql, Dialect::Postgres).unwrap();
+        Qualify("users").visit_update(&mut update);
+        assert_eq!(
+            update.to_sql(Dialect::Postgres),
+            r#"UPDATE "users" SET "score" = "users"."score" + 1 WHERE "users"."id" = $1 AND NOT EXISTS (SELECT 1 FROM "bans" WHERE "user_id" = "id") RETURNING "users"."id""#
+        );
+    }
+
+    /// Rename a column everywhere it is referenced.
+    struct Rename(&'static str, &'static str);
+
+    impl VisitMut for Rename {
+        fn visit_column_name(
+            &mut self,
+            _schema: &mut Option<String>,
+            _table: &mut Option<String>,
+            column: &mut String,
+        ) {
+            if column == self.0 {
+                *column = self.1.to_string();
+            }
+        }
+    }
+
+    #[test]
+    fn test_rename() {
+        let sql = "UPDATE users SET name = upper(name), (name, email) = ('a', 'b') RETURNING name";
+        let mut update = Update::parse(sql, Dialect::Postgres).unwrap();
+        Rename("name", "full_name").visit_update(&mut update);
+        assert_eq!(
+            update.to_sql(Dialect::Postgres),
+            r#"UPDATE "users" SET "full_name" = upper("full_name"), ("full_name", "email") = ('a', 'b') RETURNING "full_name""#
+        );
+
+        let sql = "INSERT INTO users (id, name) VALUES (1, 'a') ON CONFLICT (name) DO UPDATE SET name = excluded.name RETURNING name";
+        let mut insert = Insert::parse(sql, Dialect::Postgres).unwrap();
+        Rename("name", "full_name").visit_insert(&mut insert);
+        assert_eq!(
+            insert.to_sql(Dialect::Postgres),
+            r#"INSERT INTO "users" ("id", "full_name") VALUES (1, 'a') ON CONFLICT ("full_name") DO UPDATE SET "full_name" = "excluded"."full_name" RETURNING "full_name""#
+        );
+
+        let mut insert = Insert::new("users").columns(&["id", "name"]).on_conflict(
+            OnConflict::do_update_on_pkey("id").alternate_value("name", Expr::Raw("'a'".to_string())),
+        );
+        Rename("name", "full_name").visit_insert(&mut insert);
+        let OnConflict::DoUpdateAllRows {
+            alternate_values, ..
+        } = &insert.on_conflict
+        else {
+            unreachable!()
+        };
+        assert!(alternate_values.contains_key("full_name"));
+    }
+
+    #[test]
+    fn test_rename_schema() {
+        let sql = "CREATE TABLE users (id int NOT NULL, name text);\n\
+            CREATE TABLE posts (id int NOT NULL, author text REFERENCES users(name));\n\
+            CREATE INDEX users_name_idx ON users (name);";
+        let mut schema = Schema::try_from_ddl(sql, Dialect::Postgres).unwrap();
+        Rename("name", "full_name").visit_schema(&mut schema);
+        assert_eq!(schema.tables[0].columns[1].name, "full_name");
+        assert_eq!(
+            schema.tables[1].columns[1].constraint,
+            Some(Constraint::foreign_key(
+                "users".to_string(),
+                vec!["full_name".to_string()]
+            ))
+        );
+        assert_eq!(schema.indexes[0].columns, vec!["full_name".to_string()]);
+
+        let mut alter = AlterTable::parse(
+            "ALTER TABLE users ADD COLUMN name text, ALTER COLUMN name SET NOT NULL",
+            Dialect::Postgres,
+        )
+        .unwrap();
+        Rename("name", "full_name").visit_alter_table(&mut alter);
+        assert_eq!(
+            alter.to_sql(Dialect::Postgres),
+            r#"ALTER TABLE "users" ADD COLUMN "full_name" character varying, ALTER COLUMN "full_name" SET NOT NULL"#
+        );
+    }
+}
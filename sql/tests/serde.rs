@@ -0,0 +1,117 @@
+#![cfg(feature = "serde")]
+
+use pretty_assertions::assert_eq;
+use sql::{
+    AlterAction, AlterTable, Case, Column, Conflict, Cursor, Delete, Expr, From, Function, Index,
+    IndexKind, Insert, Join, Literal, Lock, OnConflict, OrderBy, Over, Schema, Select, Type, Update,
+    Value, Values, Where,
+};
+
+fn round_trip<T>(value: T)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(serde_json::from_str::<T>(&json).unwrap(), value);
+}
+
+#[test]
+fn test_queries() {
+    let recent = Select::default()
+        .select_raw("*")
+        .from("orders")
+        .where_raw("created_at > now() - interval '7 days'");
+    let select = Select::default()
+        .with("recent", recent)
+        .table_column("u", "id")
+        .select_expr(
+            Function::count_star()
+                .over(Over::new().partition_by(Expr::table_column("u", "team_id"))),
+            "n",
+        )
+        .select_expr(
+            Expr::case(
+                Case::new_when(Expr::table_column("u", "admin"), Literal::string("a"))
+                    .els(Literal::string("b")),
+            ),
+            "role",
+        )
+        .from(From::table("users").alias("u"))
+        .join(Join::left("recent").alias("r").on(Where::Expr(Expr::new_eq(
+            Expr::table_column("r", "user_id"),
+            Expr::table_column("u", "id"),
+        ))))
+        .where_(Where::Expr(Expr::in_list(
+            Expr::table_column("u", "id"),
+            vec![Expr::literal(1), Expr::literal(2)],
+        )))
+        .where_(Where::Expr(Expr::like(
+            Expr::table_column("u", "name"),
+            Literal::string("k%"),
+        )))
+        .group_by("u.id")
+        .order_by(OrderBy::new("u.id").desc().nulls_last())
+        .limit(10)
+        .lock(Lock::update().skip_locked());
+    round_trip(select);
+
+    round_trip(
+        Insert::new("users")
+            .columns(&["id", "name"])
+            .values(Values::new_value(
+                Value::new().expr(Literal::from(1)).param(1.5),
+            ))
+            .on_conflict(OnConflict::DoUpdate {
+                conflict: Conflict::columns(["id"]),
+                updates: vec![("name".to_string(), Expr::excluded("name"))],
+                where_: Where::And(vec![]),
+            })
+            .returning(&["*"]),
+    );
+    round_trip(
+        Update::new("users")
+            .set("name", Literal::string("b"))
+            .where_(Where::Expr(Expr::new_eq(
+                Expr::column("id"),
+                Expr::param(1),
+            ))),
+    );
+    round_trip(Delete::new("users").where_raw("id = 1").returning(&["id"]));
+    round_trip(Cursor::new(vec![Literal::Float(1.5), vec![0u8, 1].into()]));
+}
+
+#[test]
+fn test_schema() {
+    round_trip(Index {
+        name: "idx".to_string(),
+        unique: false,
+        schema: None,
+        table: "users".to_string(),
+        columns: vec!["tags".to_string()],
+        kind: IndexKind::Other("gin".to_string()),
+    });
+    round_trip(AlterTable {
+        schema: None,
+        name: "users".to_string(),
+        actions: vec![
+            AlterAction::AddColumn {
+                column: Column {
+                    name: "age".to_string(),
+                    typ: Type::I32,
+                    nullable: true,
+                    primary_key: false,
+                    default: None,
+                    constraint: None,
+                    generated: None,
+                },
+            },
+            AlterAction::set_type("name".to_string(), Type::Array(Box::new(Type::Text))),
+        ],
+    });
+}
+
+#[test]
+fn test_schema_without_indexes() {
+    let schema: Schema = serde_json::from_str(r#"{"tables": []}"#).unwrap();
+    assert!(schema.indexes.is_empty());
+}
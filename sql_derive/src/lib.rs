@@ -0,0 +1,473 @@
+//! `#[derive(SqlTable)]` implements `sql::SqlTable` for a struct with named fields, so the
+//! struct can be added to a `sql::Schema` and diffed with `sql::migrate`.
+//!
+//! ```ignore
+//! #[derive(SqlTable)]
+//! #[sql(table = "users", schema = "app", unique(org_id, email))]
+//! struct User {
+//!     #[sql(primary_key, default = "gen_random_uuid()")]
+//!     id: Uuid,
+//!     #[sql(references = "orgs(id)", index)]
+//!     org_id: Uuid,
+//!     email: String,
+//!     #[sql(type = "citext")]
+//!     name: Option<String>,
+//!     #[sql(skip)]
+//!     password: Option<String>,
+//! }
+//! ```
+//!
+//! Struct attributes:
+//! - `table = "..."`: the table name. Defaults to the struct name in snake case.
+//! - `schema = "..."`: the schema of the table.
+//! - `index(a, b)`, `unique(a, b)`: a (unique) index on the columns of the fields.
+//!
+//! Field attributes:
+//! - `rename = "..."`: the column name. Defaults to the field name.
+//! - `type = "..."`: the column type, for Rust types without a mapping, or to override it.
+//!   Type names are matched case-insensitively.
+//! - `primary_key`
+//! - `default = "..."`: the default, as a SQL expression.
+//! - `references = "table(column)"`: a foreign key. The table is in the struct's schema,
+//!   unless it is qualified, e.g. `public.orgs(id)`.
+//! - `index`, `unique`: a (unique) index on the column.
+//! - `skip`: the field is not a column.
+//!
+//! `Option<T>` columns are nullable, and other columns are NOT NULL. Types are mapped by
+//! name, so the mapping doesn't depend on which crate they come from:
+//! - `bool`, `i16`, `i32`, `i64`, `f32`, `f64`, `String`, `&str`
+//! - `Uuid`
+//! - `DateTime<Tz>`, `NaiveDateTime`, `NaiveDate`, `NaiveTime`, `TimeDelta` (chrono), and
+//!   `OffsetDateTime`, `PrimitiveDateTime`, `Date`, `Time`, `Duration` (time)
+//! - `Value` (serde_json) and `Json<T>` as `jsonb`
+//! - `Decimal`, `BigDecimal`
+//! - `Vec<u8>` as `bytea`, and other `Vec<T>` as arrays
+use convert_case::{Case, Casing};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Fields, GenericArgument, LitStr, PathArguments, Result};
+
+#[proc_macro_derive(SqlTable, attributes(sql))]
+pub fn derive_sql_table(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct IndexAttr {
+    unique: bool,
+    columns: Vec<syn::Ident>,
+}
+
+#[derive(Default)]
+struct TableAttrs {
+    table: Option<String>,
+    schema: Option<String>,
+    indexes: Vec<IndexAttr>,
+}
+
+#[derive(Default)]
+struct ColumnAttrs {
+    rename: Option<String>,
+    typ: Option<LitStr>,
+    primary_key: bool,
+    default: Option<String>,
+    references: Option<LitStr>,
+    index: bool,
+    unique: bool,
+    skip: bool,
+}
+
+fn table_attrs(input: &DeriveInput) -> Result<TableAttrs> {
+    let mut attrs = TableAttrs::default();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("sql")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table") {
+                attrs.table = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("schema") {
+                attrs.schema = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("index") || meta.path.is_ident("unique") {
+                let mut columns = vec![];
+                meta.parse_nested_meta(|column| {
+                    columns.push(column.path.require_ident()?.clone());
+                    Ok(())
+                })?;
+                attrs.indexes.push(IndexAttr {
+                    unique: meta.path.is_ident("unique"),
+                    columns,
+                });
+            } else {
+                return Err(meta.error("unknown sql attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn column_attrs(field: &syn::Field) -> Result<ColumnAttrs> {
+    let mut attrs = ColumnAttrs::default();
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("sql")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                attrs.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("type") {
+                attrs.typ = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("primary_key") {
+                attrs.primary_key = true;
+            } else if meta.path.is_ident("default") {
+                attrs.default = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("references") {
+                attrs.references = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("index") {
+                attrs.index = true;
+            } else if meta.path.is_ident("unique") {
+                attrs.unique = true;
+            } else if meta.path.is_ident("skip") {
+                attrs.skip = true;
+            } else {
+                return Err(meta.error("unknown sql attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "SqlTable can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(
+            input.span(),
+            "SqlTable can only be derived for structs with named fields",
+        ));
+    };
+    let attrs = table_attrs(&input)?;
+    let table = attrs
+        .table
+        .unwrap_or_else(|| input.ident.unraw().to_string().to_case(Case::Snake));
+
+    // Field names, and the names of their columns.
+    let mut names: Vec<(String, String)> = vec![];
+    let mut columns = vec![];
+    let mut indexes = vec![];
+    for field in &fields.named {
+        let column = column_attrs(field)?;
+        if column.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let name = column.rename.unwrap_or_else(|| ident.unraw().to_string());
+        let (typ, nullable) = match column.typ {
+            Some(typ) => (type_override(&typ)?, option_inner(&field.ty).is_some()),
+            None => column_type(&field.ty)?,
+        };
+        let primary_key = column.primary_key;
+        let default = match column.default {
+            Some(default) => quote!(Some(::sql::Expr::Raw(#default.to_string()))),
+            None => quote!(None),
+        };
+        let constraint = match column.references {
+            Some(references) => {
+                let (schema, table, columns) = foreign_key(&references)?;
+                let schema = option_string(&schema.or_else(|| attrs.schema.clone()));
+                quote!(Some(::sql::Constraint::ForeignKey(::sql::ForeignKey {
+                    schema: #schema,
+                    table: #table.to_string(),
+                    columns: vec![#(#columns.to_string()),*],
+                })))
+            }
+            None => quote!(None),
+        };
+        columns.push(quote! {
+            ::sql::Column {
+                name: #name.to_string(),
+                typ: #typ,
+                nullable: #nullable,
+                primary_key: #primary_key,
+                default: #default,
+                constraint: #constraint,
+                generated: None,
+            }
+        });
+        if column.index || column.unique {
+            indexes.push(index(
+                &table,
+                &attrs.schema,
+                column.unique,
+                std::slice::from_ref(&name),
+            ));
+        }
+        names.push((ident.unraw().to_string(), name));
+    }
+    for index_attr in &attrs.indexes {
+        let mut index_columns = vec![];
+        for ident in &index_attr.columns {
+            let field = ident.unraw().to_string();
+            let Some((_, column)) = names.iter().find(|(name, _)| *name == field) else {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("`{}` is not a column field of `{}`", field, input.ident),
+                ));
+            };
+            index_columns.push(column.clone());
+        }
+        indexes.push(index(
+            &table,
+            &attrs.schema,
+            index_attr.unique,
+            &index_columns,
+        ));
+    }
+
+    let ident = &input.ident;
+    let schema = option_string(&attrs.schema);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::sql::SqlTable for #ident #ty_generics #where_clause {
+            fn table() -> ::sql::Table {
+                ::sql::Table {
+                    schema: #schema,
+                    name: #table.to_string(),
+                    columns: vec![#(#columns),*],
+                }
+            }
+
+            fn indexes() -> Vec<::sql::Index> {
+                vec![#(#indexes),*]
+            }
+        }
+    })
+}
+
+/// Indexes are named like Postgres names them when it creates one for a constraint.
+fn index(table: &str, schema: &Option<String>, unique: bool, columns: &[String]) -> TokenStream2 {
+    let suffix = if unique { "key" } else { "idx" };
+    let name = format!("{}_{}_{}", table, columns.join("_"), suffix);
+    let schema = option_string(schema);
+    quote! {
+        ::sql::Index {
+            name: #name.to_string(),
+            unique: #unique,
+            schema: #schema,
+            table: #table.to_string(),
+            columns: vec![#(#columns.to_string()),*],
+            kind: ::sql::IndexKind::default(),
+        }
+    }
+}
+
+fn option_string(s: &Option<String>) -> TokenStream2 {
+    match s {
+        Some(s) => quote!(Some(#s.to_string())),
+        None => quote!(None),
+    }
+}
+
+/// Parse `table(column, ...)` or `table`, where the table may be qualified by its schema.
+fn foreign_key(references: &LitStr) -> Result<(Option<String>, String, Vec<String>)> {
+    let value = references.value();
+    let invalid = || {
+        Error::new(
+            references.span(),
+            "expected `table`, `schema.table` or `table(column, ...)`",
+        )
+    };
+    let (table, columns) = match value.split_once('(') {
+        Some((table, columns)) => {
+            let columns = columns.strip_suffix(')').ok_or_else(invalid)?;
+            let columns = columns.split(',').map(|c| c.trim().to_string()).collect();
+            (table.trim(), columns)
+        }
+        None => (value.trim(), vec![]),
+    };
+    let (schema, table) = match table.split_once('.') {
+        Some((schema, table)) => (Some(schema.trim().to_string()), table.trim()),
+        None => (None, table),
+    };
+    if table.is_empty()
+        || schema.as_ref().is_some_and(String::is_empty)
+        || columns.iter().any(String::is_empty)
+    {
+        return Err(invalid());
+    }
+    Ok((schema, table.to_string(), columns))
+}
+
+/// A `#[sql(type = "...")]` override, mapped like `sql::Type::from_str`, so that it is checked
+/// at compile time.
+fn type_override(typ: &LitStr) -> Result<TokenStream2> {
+    let value = typ.value();
+    let variant = match value.to_lowercase().as_str() {
+        "" => return Err(Error::new(typ.span(), "expected a column type")),
+        "array" => {
+            return Err(Error::new(
+                typ.span(),
+                "`ARRAY` needs an element type, e.g. `text[]`, or use a `Vec<T>` field",
+            ));
+        }
+        "numeric" | "decimal" => quote!(Decimal),
+        "bigint" | "int8" => quote!(I64),
+        "double precision" | "float8" => quote!(F64),
+        "real" | "float4" => quote!(F32),
+        "bool" | "boolean" => quote!(Boolean),
+        "date" => quote!(Date),
+        "bytea" => quote!(Bytes),
+        "timestamp with time zone" | "timestamptz" => quote!(DateTime),
+        "timestamp without time zone" | "timestamp" => quote!(NaiveDateTime),
+        "interval" => quote!(Duration),
+        "json" => quote!(Json),
+        "jsonb" => quote!(Jsonb),
+        "uuid" => quote!(Uuid),
+        "smallint" | "int2" => quote!(I16),
+        "text" | "character varying" | "varchar" => quote!(Text),
+        "integer" | "int" | "int4" => quote!(I32),
+        "time" | "time without time zone" => quote!(Time),
+        _ => return Ok(quote!(::sql::Type::Other(#value.to_string()))),
+    };
+    Ok(quote!(::sql::Type::#variant))
+}
+
+/// The `T` of `Option<T>`.
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    match generic(ty)? {
+        (name, Some(inner)) if name == "Option" => Some(inner),
+        _ => None,
+    }
+}
+
+/// The name of a path type, and its first type argument.
+fn generic(ty: &syn::Type) -> Option<(String, Option<&syn::Type>)> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let argument = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+    Some((segment.ident.to_string(), argument))
+}
+
+/// The column type of a field, and whether it is nullable.
+fn column_type(ty: &syn::Type) -> Result<(TokenStream2, bool)> {
+    match option_inner(ty) {
+        Some(inner) => Ok((sql_type(inner)?, true)),
+        None => Ok((sql_type(ty)?, false)),
+    }
+}
+
+fn sql_type(ty: &syn::Type) -> Result<TokenStream2> {
+    let unsupported = || {
+        Error::new(
+            ty.span(),
+            "unsupported column type, set it with #[sql(type = \"...\")]",
+        )
+    };
+    let typ = match ty {
+        syn::Type::Reference(reference) => return sql_type(&reference.elem),
+        syn::Type::Slice(slice) if is_u8(&slice.elem) => quote!(Bytes),
+        syn::Type::Array(array) if is_u8(&array.elem) => quote!(Bytes),
+        _ => match generic(ty)
+            .as_ref()
+            .map(|(name, inner)| (name.as_str(), *inner))
+            .ok_or_else(unsupported)?
+        {
+            ("bool", _) => quote!(Boolean),
+            ("i8" | "u8" | "i16", _) => quote!(I16),
+            ("u16" | "i32", _) => quote!(I32),
+            ("u32" | "i64", _) => quote!(I64),
+            ("f32", _) => quote!(F32),
+            ("f64", _) => quote!(F64),
+            ("String" | "str", _) => quote!(Text),
+            ("Uuid", _) => quote!(Uuid),
+            ("DateTime" | "OffsetDateTime", _) => quote!(DateTime),
+            ("NaiveDateTime" | "PrimitiveDateTime", _) => quote!(NaiveDateTime),
+            ("NaiveDate" | "Date", _) => quote!(Date),
+            ("NaiveTime" | "Time", _) => quote!(Time),
+            ("TimeDelta" | "Duration", _) => quote!(Duration),
+            ("Value" | "Json", _) => quote!(Jsonb),
+            ("Decimal" | "BigDecimal", _) => quote!(Decimal),
+            ("Vec", Some(inner)) if is_u8(inner) => quote!(Bytes),
+            ("Vec", Some(inner)) => {
+                // Array elements are always nullable.
+                let inner = sql_type(option_inner(inner).unwrap_or(inner))?;
+                return Ok(quote!(::sql::Type::Array(Box::new(#inner))));
+            }
+            _ => return Err(unsupported()),
+        },
+    };
+    Ok(quote!(::sql::Type::#typ))
+}
+
+fn is_u8(ty: &syn::Type) -> bool {
+    matches!(generic(ty), Some((name, None)) if name == "u8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(input: DeriveInput) -> String {
+        expand(input).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_type_override() {
+        let input = syn::parse_quote! {
+            struct Post {
+                #[sql(type = "ARRAY")]
+                tags: Vec<String>,
+            }
+        };
+        assert_eq!(
+            error(input),
+            "`ARRAY` needs an element type, e.g. `text[]`, or use a `Vec<T>` field"
+        );
+        let typ = type_override(&syn::parse_quote!("int8")).unwrap();
+        assert_eq!(typ.to_string(), quote!(::sql::Type::I64).to_string());
+        let typ = type_override(&syn::parse_quote!("BIGINT")).unwrap();
+        assert_eq!(typ.to_string(), quote!(::sql::Type::I64).to_string());
+        let typ = type_override(&syn::parse_quote!("CITEXT")).unwrap();
+        assert_eq!(
+            typ.to_string(),
+            quote!(::sql::Type::Other("CITEXT".to_string())).to_string()
+        );
+    }
+
+    #[test]
+    fn test_foreign_key() {
+        let (schema, table, columns) = foreign_key(&syn::parse_quote!("public.orgs(id)")).unwrap();
+        assert_eq!(schema.as_deref(), Some("public"));
+        assert_eq!(table, "orgs");
+        assert_eq!(columns, vec!["id".to_string()]);
+        assert!(foreign_key(&syn::parse_quote!(".orgs(id)")).is_err());
+    }
+
+    #[test]
+    fn test_index_fields() {
+        let input = syn::parse_quote! {
+            #[sql(unique(email_address))]
+            struct User {
+                #[sql(rename = "email_address")]
+                email: String,
+            }
+        };
+        assert_eq!(
+            error(input),
+            "`email_address` is not a column field of `User`"
+        );
+    }
+}
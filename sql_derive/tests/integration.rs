@@ -0,0 +1,111 @@
+use pretty_assertions::assert_eq;
+use sql::{Dialect, MigrationOptions, Schema, SqlTable, ToSql};
+use sql_derive::SqlTable;
+
+// Columns are mapped by type name, so stand-ins for the uuid and chrono types are enough.
+mod uuid {
+    pub struct Uuid;
+}
+
+mod chrono {
+    pub struct Utc;
+    pub struct DateTime<Tz>(Tz);
+    pub struct NaiveDate;
+}
+
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+#[allow(dead_code)]
+#[derive(SqlTable)]
+#[sql(schema = "app", unique(org_id, email), index(name))]
+struct User {
+    #[sql(primary_key, default = "gen_random_uuid()")]
+    id: Uuid,
+    #[sql(references = "org(id)", index)]
+    org_id: Uuid,
+    email: String,
+    #[sql(rename = "display_name", type = "citext")]
+    name: Option<String>,
+    age: Option<i32>,
+    #[sql(type = "BIGINT")]
+    visits: i32,
+    tags: Vec<String>,
+    avatar: Option<Vec<u8>>,
+    settings: serde_json::Value,
+    birthday: Option<NaiveDate>,
+    created_at: DateTime<Utc>,
+    #[sql(skip)]
+    password: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(SqlTable)]
+#[sql(table = "org", schema = "app")]
+struct Organization {
+    #[sql(primary_key)]
+    id: Uuid,
+    #[sql(unique)]
+    name: String,
+}
+
+#[test]
+fn test_table() {
+    let table = User::table();
+    assert_eq!(
+        table.to_sql(Dialect::Postgres),
+        r#"CREATE TABLE IF NOT EXISTS "app"."user" (
+"id" uuid NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+"org_id" uuid NOT NULL REFERENCES "app"."org"("id"),
+"email" character varying NOT NULL,
+"display_name" citext,
+"age" integer,
+"visits" bigint NOT NULL,
+"tags" character varying[] NOT NULL,
+"avatar" bytea,
+"settings" jsonb NOT NULL,
+"birthday" date,
+"created_at" timestamptz NOT NULL
+)"#
+    );
+    let indexes: Vec<String> = User::indexes()
+        .iter()
+        .map(|index| index.to_sql(Dialect::Postgres))
+        .collect();
+    assert_eq!(
+        indexes,
+        vec![
+            r#"CREATE INDEX "user_org_id_idx" ON "app"."user" ("org_id")"#,
+            r#"CREATE UNIQUE INDEX "user_org_id_email_key" ON "app"."user" ("org_id", "email")"#,
+            r#"CREATE INDEX "user_display_name_idx" ON "app"."user" ("display_name")"#,
+        ]
+    );
+}
+
+#[test]
+fn test_migrate() {
+    let mut desired = Schema::default();
+    desired.add::<Organization>();
+    desired.add::<User>();
+    assert_eq!(desired.indexes.len(), 4);
+    let migration = Schema::default()
+        .migrate_to(desired, &MigrationOptions::default())
+        .unwrap();
+    let statements: Vec<String> = migration
+        .statements
+        .iter()
+        .map(|s| s.to_sql(Dialect::Postgres))
+        .collect();
+    assert!(statements[0].starts_with(r#"CREATE TABLE IF NOT EXISTS "app"."org" ("#));
+    assert!(statements[1].starts_with(r#"CREATE TABLE IF NOT EXISTS "app"."user" ("#));
+    // The indexes are created after the tables.
+    assert_eq!(
+        statements[2..],
+        [
+            r#"CREATE UNIQUE INDEX "org_name_key" ON "app"."org" ("name")"#,
+            r#"CREATE INDEX "user_org_id_idx" ON "app"."user" ("org_id")"#,
+            r#"CREATE UNIQUE INDEX "user_org_id_email_key" ON "app"."user" ("org_id", "email")"#,
+            r#"CREATE INDEX "user_display_name_idx" ON "app"."user" ("display_name")"#,
+        ]
+    );
+}
@@ -11,38 +11,23 @@ pub trait FromOpenApi: Sized {
     ) -> anyhow::Result<Self>;
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FromOpenApiOptions {
     pub include_arrays: bool,
     pub include_schemas: Vec<String>,
 }
 
-impl Default for FromOpenApiOptions {
-    fn default() -> Self {
-        Self {
-            include_arrays: false,
-            include_schemas: vec![],
-        }
-    }
-}
-
 impl FromOpenApi for Schema {
     fn try_from_openapi(spec: oa::OpenAPI, options: &FromOpenApiOptions) -> anyhow::Result<Self> {
         let mut tables = Vec::new();
         for (schema_name, schema) in spec.schemas.iter().filter(|(schema_name, _)| {
-            if options.include_schemas.contains(schema_name) {
-                true
-            } else if schema_name.ends_with("Response") {
-                false
-            } else {
-                true
-            }
+            options.include_schemas.contains(schema_name) || !schema_name.ends_with("Response")
         }) {
             let schema = schema.resolve(&spec);
-            let Some(mut columns) = schema_to_columns(&schema, &spec, options)? else {
+            let Some(mut columns) = schema_to_columns(schema, &spec, options)? else {
                 continue;
             };
-            let pkey_candidates = pkey_column_names(&schema_name);
+            let pkey_candidates = pkey_column_names(schema_name);
             for col in &mut columns {
                 if pkey_candidates.contains(&col.name) {
                     col.primary_key = true;
@@ -56,7 +41,10 @@ impl FromOpenApi for Schema {
             };
             tables.push(table);
         }
-        Ok(Schema { tables })
+        Ok(Schema {
+            tables,
+            ..Schema::default()
+        })
     }
 }
 
@@ -122,7 +110,7 @@ fn schema_to_columns(
         if primary_key {
             nullable = false;
         }
-        if prop.is_required(&name) {
+        if prop.is_required(name) {
             nullable = false;
         }
         if prop
@@ -191,11 +179,11 @@ mod test {
 
         let int_format_date = &columns[0];
         assert_eq!(int_format_date.name, "date");
-        assert_eq!(int_format_date.nullable, true);
+        assert!(int_format_date.nullable);
 
         let int_null_as_zero = &columns[1];
         assert_eq!(int_null_as_zero.name, "int_null_as_zero");
-        assert_eq!(int_null_as_zero.nullable, true);
+        assert!(int_null_as_zero.nullable);
     }
 
     #[test]
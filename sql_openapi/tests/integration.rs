@@ -26,7 +26,7 @@ pub fn test_run_sql_migration() -> Result<()> {
     assert_eq!(migration.statements.len(), 5);
     for statement in migration.statements {
         let statement = statement.to_sql(Dialect::Postgres);
-        assert!(statement.starts_with(r#"CREATE TABLE "public".""#));
+        assert!(statement.starts_with(r#"CREATE TABLE IF NOT EXISTS "public".""#));
     }
     Ok(())
 }
@@ -7,7 +7,7 @@ async fn main() {
     let mut conn = PgConnection::connect(&std::env::var("DATABASE_URL").unwrap())
         .await
         .unwrap();
-    let schema = Schema::try_from_postgres(&mut conn, "public")
+    let _schema = Schema::try_from_postgres(&mut conn, "public")
         .await
         .unwrap();
     let functions = query_functions(&mut conn, "public").await.unwrap();
@@ -88,6 +88,27 @@ pub struct Index {
     pub columns: Vec<String>,
 }
 
+impl From<Index> for schema::Index {
+    fn from(index: Index) -> Self {
+        let kind = match index.kind.as_str() {
+            "btree" => schema::IndexKind::BTree,
+            "hash" => schema::IndexKind::Hash,
+            "gist" => schema::IndexKind::Gist,
+            "spgist" => schema::IndexKind::SpGist,
+            "brin" => schema::IndexKind::Brin,
+            _ => schema::IndexKind::Other(index.kind),
+        };
+        schema::Index {
+            name: index.name,
+            unique: index.unique,
+            schema: Some(index.schema),
+            table: index.table,
+            columns: index.columns,
+            kind,
+        }
+    }
+}
+
 pub async fn query_indices(conn: &mut PgConnection, schema_name: &str) -> Result<Vec<Index>> {
     // because of pg_tables join, this only returns indices for tables, not views/mat views
     let s = include_str!("sql/query_indices.sql");
@@ -216,7 +237,7 @@ impl FromPostgres for Schema {
         let constraints = query_constraints(conn, schema).await?;
         let mut it_tables = tables.iter_mut().peekable();
         for fk in constraints {
-            while &fk.table_name != &it_tables.peek().unwrap().name {
+            while fk.table_name != it_tables.peek().unwrap().name {
                 it_tables.next();
             }
             let table = it_tables.peek_mut().unwrap();
@@ -226,6 +247,7 @@ impl FromPostgres for Schema {
                 .find(|c| c.name == fk.column_name)
                 .expect("Constraint for unknown column.");
             column.constraint = Some(schema::Constraint::ForeignKey(schema::ForeignKey {
+                schema: Some(fk.foreign_table_schema),
                 table: fk.foreign_table_name,
                 columns: vec![fk.foreign_column_name],
             }));
@@ -237,7 +259,7 @@ impl FromPostgres for Schema {
         let mut empty_tables = Vec::new();
         'outer: for name in table_names {
             while let Some(table) = tables_it.peek() {
-                if &name == &table.name {
+                if name == table.name {
                     tables_it.next();
                     continue 'outer;
                 }
@@ -248,7 +270,12 @@ impl FromPostgres for Schema {
                 columns: vec![],
             })
         }
-        Ok(Schema { tables })
+        let indexes = query_indices(conn, schema)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        Ok(Schema { tables, indexes })
     }
 }
 
@@ -268,6 +295,9 @@ mod test {
             numeric_scale: Some(2),
             inner_type: None,
             primary_key: false,
+            generation_time: None,
+            generation_expression: None,
+            identity_generation: None,
         };
         let column: Column = c.try_into().unwrap();
         assert_eq!(column.typ, schema::Type::Numeric(10, 2));
@@ -285,6 +315,9 @@ mod test {
             numeric_scale: Some(0),
             inner_type: None,
             primary_key: false,
+            generation_time: None,
+            generation_expression: None,
+            identity_generation: None,
         };
         let column: Column = c.try_into().unwrap();
         assert_eq!(column.typ, schema::Type::I32);